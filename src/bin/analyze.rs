@@ -1,12 +1,41 @@
 use busy_beaver::{
-    report::Report,
-    turing::{Machine, Programs, SimpleTape},
+    database::DatabaseReader,
+    decider::{
+        BackwardReasoning, BouncerDetector, ClosedTapeLanguage, CounterDetector, CycleDetector,
+        FiniteAutomataReduction, HaltingSegment, Pipeline, QuasihaltDetector, Reachability,
+        Simulation, TranslatedCycler,
+    },
+    report::{LogWriter, Report},
+    turing::{CompleteProgram, Format, Programs},
 };
 use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
 use std::time::Instant;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let (options, args): (Vec<String>, Vec<String>) =
+        env::args().partition(|arg| arg.starts_with("--"));
+    let reduction: Option<FiniteAutomataReduction> = options
+        .iter()
+        .find_map(|option| option.strip_prefix("--far="))
+        .and_then(|input| input.parse().ok())
+        .map(FiniteAutomataReduction::new);
+    let ties: usize = options
+        .iter()
+        .find_map(|option| option.strip_prefix("--ties="))
+        .and_then(|input| input.parse().ok())
+        .unwrap_or(10);
+    let json: Option<&str> = options
+        .iter()
+        .find_map(|option| option.strip_prefix("--json="));
+    let mut log: Option<LogWriter<BufWriter<File>>> = options
+        .iter()
+        .find_map(|option| option.strip_prefix("--log="))
+        .map(|path| {
+            let file = File::create(path).expect("log to be writable");
+            LogWriter::new(BufWriter::new(file)).expect("a log")
+        });
     let n: u8 = args
         .get(1)
         .and_then(|input| input.parse().ok())
@@ -15,14 +44,48 @@ fn main() {
         .get(2)
         .and_then(|input| input.parse().ok())
         .unwrap_or(10_000);
+    let mut database: Option<DatabaseReader<File>> = args.get(3).map(|path| {
+        let file = File::open(path).expect("database to be readable");
+        DatabaseReader::with_states(file, n).expect("a database")
+    });
 
-    let mut report = Report::new();
+    // Reachability is cheap, but a machine that can not reach a halting transition may still quasihalt. So it only comes after
+    // the quasihalt detector, in order not to hide beeping busy beaver champions.
+    let mut pipeline = Pipeline::new()
+        .with(Simulation::new(maximum))
+        .with(QuasihaltDetector::new(maximum))
+        .with(Reachability)
+        .with(CycleDetector::new(maximum))
+        .with(TranslatedCycler::new(maximum))
+        .with(BouncerDetector::new(maximum))
+        .with(CounterDetector::new(maximum))
+        .with(ClosedTapeLanguage::new(3))
+        .with(BackwardReasoning::new(20))
+        .with(HaltingSegment::new(6));
+    if let Some(reduction) = reduction {
+        pipeline = pipeline.with(reduction);
+    }
+    let mut report = Report::new().with_ties(ties);
     let start = Instant::now();
-    for program in Programs::all(n) {
+    let programs: Box<dyn Iterator<Item = CompleteProgram>> = match database.as_mut() {
+        Some(reader) => {
+            let range = 0..reader.len();
+            Box::new(
+                reader
+                    .programs(range)
+                    .map(|program| program.expect("a program")),
+            )
+        }
+        None => Box::new(Programs::all(n)),
+    };
+    for program in programs {
         print!(".");
-        let mut machine = Machine::new(SimpleTape::empty(), &program);
-        let assessment = machine.run(maximum);
-        report.update_with(&assessment);
+        let verdict = pipeline.run(&program);
+        let formatted = program.display_as(Format::Standard);
+        if let Some(log) = log.as_mut() {
+            log.write_verdict(&formatted, &verdict).expect("an entry");
+        }
+        report.update_with_program_verdict(&formatted, &verdict);
     }
     let duration = start.elapsed();
 
@@ -30,4 +93,10 @@ fn main() {
         "\nn={}, maximum={}, duration={:?}: {:?}",
         n, maximum, duration, report
     );
+    for (name, champion) in report.champions() {
+        println!("{} champion: {}", name, champion);
+    }
+    if let Some(path) = json {
+        fs::write(path, report.to_json()).expect("report to be writable");
+    }
 }
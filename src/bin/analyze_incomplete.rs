@@ -1,13 +1,32 @@
 use busy_beaver::{
-    report::Report,
-    turing::{Details, IncompleteProgram, Machine, Program, Progress, SimpleTape, State, Tape},
+    database::DatabaseReader,
+    report::{LogWriter, Report},
+    turing::{Format, IncompleteProgram, SimpleTape, TreeNormalForm},
 };
 
 use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
 use std::time::Instant;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let (options, args): (Vec<String>, Vec<String>) =
+        env::args().partition(|arg| arg.starts_with("--"));
+    let ties: usize = options
+        .iter()
+        .find_map(|option| option.strip_prefix("--ties="))
+        .and_then(|input| input.parse().ok())
+        .unwrap_or(10);
+    let json: Option<&str> = options
+        .iter()
+        .find_map(|option| option.strip_prefix("--json="));
+    let mut log: Option<LogWriter<BufWriter<File>>> = options
+        .iter()
+        .find_map(|option| option.strip_prefix("--log="))
+        .map(|path| {
+            let file = File::create(path).expect("log to be writable");
+            LogWriter::new(BufWriter::new(file)).expect("a log")
+        });
     let n: u8 = args
         .get(1)
         .and_then(|input| input.parse().ok())
@@ -16,55 +35,31 @@ fn main() {
         .get(2)
         .and_then(|input| input.parse().ok())
         .unwrap_or(10_000);
+    let mut database: Option<DatabaseReader<File>> = args.get(3).map(|path| {
+        let file = File::open(path).expect("database to be readable");
+        DatabaseReader::with_states(file, n).expect("a database")
+    });
 
-    let mut report = Report::new();
-    let mut candidates: Vec<(u128, SimpleTape, State, IncompleteProgram)> = vec![(
-        0,
-        SimpleTape::empty(),
-        State::Number(0),
-        IncompleteProgram::with_states(n),
-    )];
+    let mut report = Report::new().with_ties(ties);
+    let trees: Box<dyn Iterator<Item = TreeNormalForm<SimpleTape>>> = match database.as_mut() {
+        Some(reader) => {
+            let range = 0..reader.len();
+            Box::new(reader.incomplete_programs(range).map(
+                move |program: Result<IncompleteProgram, _>| {
+                    TreeNormalForm::extending(program.expect("a program"), maximum)
+                },
+            ))
+        }
+        None => Box::new(std::iter::once(TreeNormalForm::new(n, maximum))),
+    };
     let start = Instant::now();
-    while let Some((steps_taken, tape, state, program)) = candidates.pop() {
+    for (program, assessment) in trees.flatten() {
         print!(".");
-        let mut step_count = steps_taken;
-        let mut machine = Machine::with(tape, state, &program);
-        loop {
-            let progress = machine.step();
-
-            match progress {
-                Progress::Made => {
-                    step_count += 1;
-                    if step_count >= maximum {
-                        report.indeterminated(Details {
-                            steps: step_count,
-                            score: 0,
-                            multiplicity: program.multiplicity(),
-                        });
-                        break;
-                    }
-                }
-                Progress::Halted => {
-                    let details = Details {
-                        steps: step_count,
-                        score: machine.score(),
-                        multiplicity: program.multiplicity(),
-                    };
-                    report.halted(details);
-                    break;
-                }
-                Progress::Limbo => {
-                    let (t, s, _): (SimpleTape, State, &dyn Program) = machine.into();
-                    for p in program.extentions((s, t.read())) {
-                        candidates.push((step_count, t.clone(), s, p));
-                    }
-                    break;
-                }
-                Progress::Stuck => {
-                    panic!("Incomplete programs should not get stuck.")
-                }
-            }
+        let formatted = program.display_as(Format::Standard);
+        if let Some(log) = log.as_mut() {
+            log.write(&formatted, &assessment).expect("an entry");
         }
+        report.update_with_program(&formatted, &assessment);
     }
     let duration = start.elapsed();
 
@@ -72,4 +67,10 @@ fn main() {
         "\nn={}, maximum={}, duration={:?}: {:?}",
         n, maximum, duration, report
     );
+    for (name, champion) in report.champions() {
+        println!("{} champion: {}", name, champion);
+    }
+    if let Some(path) = json {
+        fs::write(path, report.to_json()).expect("report to be writable");
+    }
 }
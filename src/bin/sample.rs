@@ -0,0 +1,54 @@
+use busy_beaver::{
+    report::Report,
+    turing::{Machine, Sampler, SimpleTape},
+};
+use std::env;
+use std::time::Instant;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let n: u8 = args
+        .get(1)
+        .and_then(|input| input.parse().ok())
+        .unwrap_or(5);
+    let maximum: u128 = args
+        .get(2)
+        .and_then(|input| input.parse().ok())
+        .unwrap_or(10_000);
+    let samples: usize = args
+        .get(3)
+        .and_then(|input| input.parse().ok())
+        .unwrap_or(10_000);
+    let seed: u64 = args
+        .get(4)
+        .and_then(|input| input.parse().ok())
+        .unwrap_or(0);
+    let leafs = args.get(5).map(|mode| mode == "leafs").unwrap_or(false);
+
+    let mut sampler = Sampler::new(n, seed);
+    let mut report = Report::sampling();
+    let start = Instant::now();
+    for _ in 0..samples {
+        let assessment = if leafs {
+            let (_, assessment) = sampler.leaf::<SimpleTape>(maximum);
+            assessment
+        } else {
+            let program = sampler.program();
+            let mut machine = Machine::new(SimpleTape::empty(), &program);
+            machine.run(maximum)
+        };
+        report.update_with(&assessment);
+    }
+    let duration = start.elapsed();
+
+    println!(
+        "n={}, maximum={}, samples={}, seed={}, duration={:?}: {:?}\n{:?}",
+        n,
+        maximum,
+        samples,
+        seed,
+        duration,
+        report,
+        report.estimates()
+    );
+}
@@ -0,0 +1,29 @@
+use busy_beaver::turing::{CompleteProgram, Format};
+use busy_beaver::verifier::{verify, Certificate};
+use std::env;
+use std::fs;
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program: CompleteProgram = args
+        .get(1)
+        .and_then(|input| {
+            input
+                .parse()
+                .or_else(|_| CompleteProgram::parse_as(input, Format::Standard))
+                .ok()
+        })
+        .expect("a program in the native or the standard format");
+    let input = args
+        .get(2)
+        .map(|path| fs::read_to_string(path).expect("certificate to be readable"))
+        .expect("a path to a certificate");
+    let certificate: Certificate = input.parse().expect("a certificate");
+    if verify(&program, &certificate) {
+        println!("{} never halts", program);
+    } else {
+        println!("{} is not proven to never halt", program);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,525 @@
+//! Read and write programs in the binary format of the [bbchallenge seed database](https://bbchallenge.org/method#seed-database).
+//!
+//! The database starts with a header of 30 bytes
+//! * bytes 0 to 4 contain the number of machines that were undecided because of the time limit.
+//! * bytes 4 to 8 contain the number of machines that were undecided because of the space limit.
+//! * bytes 8 to 12 contain the total number of machines in the database.
+//! * byte 12 signals if the machines are sorted.
+//!
+//! All numbers are big-endian. The header is followed by the machines, 30 bytes per machine. Each transition of the machine takes
+//! three bytes: the symbol to write, the direction to move in, `0` for right and `1` for left, and the state to go to, starting at
+//! `1` for the first state. Undefined transitions, which halt the machine, are written as three zeros.
+//!
+//! The following code reads all the machines in a database
+//!
+//! ```
+//! # use busy_beaver::database::{DatabaseReader, DatabaseWriter, Header};
+//! # use busy_beaver::turing::CompleteProgram;
+//! # use std::io::Cursor;
+//! # let program: CompleteProgram = "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3   H 0L0".parse().unwrap();
+//! # let mut output = Vec::new();
+//! # let mut writer = DatabaseWriter::new(&mut output, &Header::with_total(1)).unwrap();
+//! # writer.write(&program).unwrap();
+//! # let file = Cursor::new(output);
+//! let mut reader = DatabaseReader::new(file).expect("a database");
+//! for program in reader.programs(0..reader.len()) {
+//!     println!("{}", program.expect("a program"));
+//! }
+//! ```
+use crate::turing::{
+    Action, CompleteProgram, Direction, IncompleteProgram, Keys, Lookup, Program, State, Symbol,
+};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+/// The number of bytes in the header of a database.
+pub const HEADER_SIZE: usize = 30;
+
+/// The number of states of the machines in the seed database.
+pub const STATES: u8 = 5;
+
+/// The header of a database
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Header {
+    /// Number of machines that were undecided because of the time limit
+    pub undecided_time: u32,
+    /// Number of machines that were undecided because of the space limit
+    pub undecided_space: u32,
+    /// Total number of machines in the database
+    pub total: u32,
+    /// Whether the machines are sorted
+    pub sorted: bool,
+}
+
+impl Header {
+    /// Create a header for a database with a total number of machines.
+    pub fn with_total(total: u32) -> Self {
+        Self {
+            undecided_time: 0,
+            undecided_space: 0,
+            total,
+            sorted: false,
+        }
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_SIZE]) -> Self {
+        let number = |offset: usize| {
+            u32::from_be_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        Self {
+            undecided_time: number(0),
+            undecided_space: number(4),
+            total: number(8),
+            sorted: bytes[12] != 0,
+        }
+    }
+
+    fn to_bytes(self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&self.undecided_time.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.undecided_space.to_be_bytes());
+        bytes[8..12].copy_from_slice(&self.total.to_be_bytes());
+        bytes[12] = self.sorted as u8;
+        bytes
+    }
+}
+
+/// Reads programs from a database.
+pub struct DatabaseReader<R> {
+    input: R,
+    header: Header,
+    n: u8,
+}
+
+impl<R> DatabaseReader<R>
+where
+    R: Read + Seek,
+{
+    /// Create a reader for a database of machines with five states.
+    pub fn new(input: R) -> Result<Self, Error> {
+        Self::with_states(input, STATES)
+    }
+
+    /// Create a reader for a database of machines with `n` states.
+    pub fn with_states(mut input: R, n: u8) -> Result<Self, Error> {
+        let mut bytes = [0u8; HEADER_SIZE];
+        input.seek(SeekFrom::Start(0))?;
+        input.read_exact(&mut bytes)?;
+        let header = Header::from_bytes(&bytes);
+        Ok(Self { input, header, n })
+    }
+
+    /// The header of the database
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The number of machines in the database
+    pub fn len(&self) -> u64 {
+        self.header.total as u64
+    }
+
+    /// Determine if the database contains no machines.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the complete program with a certain index. Undefined transitions halt.
+    pub fn get(&mut self, index: u64) -> Result<CompleteProgram, Error> {
+        self.seek(index)?;
+        self.read_complete()
+    }
+
+    /// Read the incomplete program with a certain index. Undefined transitions are indeterminate.
+    pub fn get_incomplete(&mut self, index: u64) -> Result<IncompleteProgram, Error> {
+        self.seek(index)?;
+        self.read_incomplete()
+    }
+
+    /// Stream the complete programs with an index in a range.
+    pub fn programs(&mut self, range: Range<u64>) -> Records<'_, R, CompleteProgram> {
+        Records::new(self, range, Self::read_complete)
+    }
+
+    /// Stream the incomplete programs with an index in a range.
+    pub fn incomplete_programs(&mut self, range: Range<u64>) -> Records<'_, R, IncompleteProgram> {
+        Records::new(self, range, Self::read_incomplete)
+    }
+
+    fn seek(&mut self, index: u64) -> Result<(), Error> {
+        if index < self.len() {
+            let offset = HEADER_SIZE as u64 + index * record_size(self.n) as u64;
+            self.input.seek(SeekFrom::Start(offset))?;
+            Ok(())
+        } else {
+            Err(Error::OutOfRange(index))
+        }
+    }
+
+    fn read_complete(&mut self) -> Result<CompleteProgram, Error> {
+        let mut program = CompleteProgram::new();
+        for (index, action) in self.read_transitions()?.into_iter().enumerate() {
+            program.insert(index, action.unwrap_or(Action::Halt));
+        }
+        Ok(program)
+    }
+
+    fn read_incomplete(&mut self) -> Result<IncompleteProgram, Error> {
+        let mut program = IncompleteProgram::with_states(self.n);
+        for (index, action) in self.read_transitions()?.into_iter().enumerate() {
+            if let Some(action) = action {
+                program.insert(index, action);
+            }
+        }
+        Ok(program)
+    }
+
+    fn read_transitions(&mut self) -> Result<Vec<Option<Action>>, Error> {
+        let mut bytes = vec![0u8; record_size(self.n)];
+        self.input.read_exact(&mut bytes)?;
+        bytes.chunks(3).map(|chunk| decode(chunk, self.n)).collect()
+    }
+}
+
+/// Iterator that streams programs from a database.
+pub struct Records<'a, R, P> {
+    reader: &'a mut DatabaseReader<R>,
+    range: Range<u64>,
+    positioned: bool,
+    failed: bool,
+    read: fn(&mut DatabaseReader<R>) -> Result<P, Error>,
+}
+
+impl<'a, R, P> Records<'a, R, P>
+where
+    R: Read + Seek,
+{
+    fn new(
+        reader: &'a mut DatabaseReader<R>,
+        range: Range<u64>,
+        read: fn(&mut DatabaseReader<R>) -> Result<P, Error>,
+    ) -> Self {
+        Self {
+            reader,
+            range,
+            positioned: false,
+            failed: false,
+            read,
+        }
+    }
+}
+
+impl<'a, R, P> Iterator for Records<'a, R, P>
+where
+    R: Read + Seek,
+{
+    type Item = Result<P, Error>;
+
+    /// The iterator ends after the first error, because the input is no longer aligned with the records.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let record = self.range.next().map(|index| {
+            if !self.positioned {
+                self.reader.seek(index)?;
+                self.positioned = true;
+            } else if index >= self.reader.len() {
+                return Err(Error::OutOfRange(index));
+            }
+            (self.read)(self.reader)
+        });
+        self.failed = matches!(record, Some(Err(_)));
+        record
+    }
+}
+
+/// Writes programs to a database.
+///
+/// The header is written before the programs. When the number of programs is not known in advance, e.g. when writing a filtered
+/// subset of a database, `finish` corrects the total in the header afterwards.
+pub struct DatabaseWriter<W> {
+    output: W,
+    header: Header,
+    written: u32,
+    n: u8,
+}
+
+impl<W> DatabaseWriter<W>
+where
+    W: Write,
+{
+    /// Create a writer for a database of machines with five states. The header is written immediately.
+    pub fn new(output: W, header: &Header) -> Result<Self, Error> {
+        Self::with_states(output, header, STATES)
+    }
+
+    /// Create a writer for a database of machines with `n` states. The header is written immediately.
+    pub fn with_states(mut output: W, header: &Header, n: u8) -> Result<Self, Error> {
+        output.write_all(&header.to_bytes())?;
+        Ok(Self {
+            output,
+            header: *header,
+            written: 0,
+            n,
+        })
+    }
+
+    /// Write a program to the database. Halting and indeterminate transitions are written as undefined.
+    ///
+    /// The database only holds programs over an alphabet of two symbols, other programs are rejected.
+    pub fn write(&mut self, program: &dyn Program) -> Result<(), Error> {
+        if program.symbols() != 2 {
+            return Err(Error::UnsupportedSymbols(program.symbols()));
+        }
+        let mut bytes = Vec::with_capacity(record_size(self.n));
+        for key in Keys::up_to(self.n) {
+            match program.lookup(&key) {
+                Lookup::Determined(Action::Do {
+                    symbol,
+                    direction,
+                    state: State::Number(s),
+                }) => bytes.extend_from_slice(&[
+                    symbol_to_byte(symbol),
+                    direction_to_byte(direction),
+                    s + 1,
+                ]),
+                _ => bytes.extend_from_slice(&[0, 0, 0]),
+            }
+        }
+        self.output.write_all(&bytes)?;
+        self.written += 1;
+        Ok(())
+    }
+}
+
+impl<W> DatabaseWriter<W>
+where
+    W: Write + Seek,
+{
+    /// Finish the database, rewriting the header with the total number of programs written. Returns the output.
+    pub fn finish(mut self) -> Result<W, Error> {
+        let header = Header {
+            total: self.written,
+            ..self.header
+        };
+        self.output.seek(SeekFrom::Start(0))?;
+        self.output.write_all(&header.to_bytes())?;
+        self.output.seek(SeekFrom::End(0))?;
+        self.output.flush()?;
+        Ok(self.output)
+    }
+}
+
+fn record_size(n: u8) -> usize {
+    6 * n as usize
+}
+
+fn decode(chunk: &[u8], n: u8) -> Result<Option<Action>, Error> {
+    if chunk[2] == 0 {
+        return match (chunk[0], chunk[1]) {
+            (0, 0) => Ok(None),
+            (symbol, direction) => Err(Error::UndefinedTransition(symbol, direction)),
+        };
+    }
+    let symbol = match chunk[0] {
+        0 => Symbol::BLANK,
+        1 => Symbol::NON_BLANK,
+        b => return Err(Error::UnknownSymbol(b)),
+    };
+    let direction = match chunk[1] {
+        0 => Direction::Right,
+        1 => Direction::Left,
+        b => return Err(Error::UnknownDirection(b)),
+    };
+    match chunk[2] {
+        s if s <= n => Ok(Some(Action::Do {
+            symbol,
+            direction,
+            state: State::Number(s - 1),
+        })),
+        s => Err(Error::UnknownState(s)),
+    }
+}
+
+fn symbol_to_byte(symbol: Symbol) -> u8 {
+    symbol.digit()
+}
+
+fn direction_to_byte(direction: Direction) -> u8 {
+    match direction {
+        Direction::Right => 0,
+        Direction::Left => 1,
+    }
+}
+
+/// The problems that can occur when reading or writing a database.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing failed
+    Io(io::Error),
+    /// The index is beyond the number of machines in the database
+    OutOfRange(u64),
+    /// A transition contains an unknown symbol
+    UnknownSymbol(u8),
+    /// A transition contains an unknown direction
+    UnknownDirection(u8),
+    /// A transition contains an unknown state
+    UnknownState(u8),
+    /// An undefined transition, i.e. one with state `0`, has a symbol or a direction other than `0`
+    UndefinedTransition(u8, u8),
+    /// A program to write has an alphabet of other than two symbols
+    UnsupportedSymbols(u8),
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::Format;
+    use std::io::Cursor;
+
+    fn database(descriptions: &[&str]) -> Cursor<Vec<u8>> {
+        let mut output = Vec::new();
+        let header = Header::with_total(descriptions.len() as u32);
+        let mut writer = DatabaseWriter::with_states(&mut output, &header, 2).unwrap();
+        for description in descriptions {
+            let program: CompleteProgram = description.parse().unwrap();
+            writer.write(&program).unwrap();
+        }
+        Cursor::new(output)
+    }
+
+    #[test]
+    fn header_can_be_read() {
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[3] = 14;
+        bytes[7] = 2;
+        bytes[11] = 16;
+        bytes[12] = 1;
+
+        assert_eq!(
+            Header {
+                undecided_time: 14,
+                undecided_space: 2,
+                total: 16,
+                sorted: true
+            },
+            Header::from_bytes(&bytes)
+        );
+    }
+
+    #[test]
+    fn programs_are_written_as_records() {
+        let data = database(&["1R1 1L1 1L0   H"]).into_inner();
+
+        assert_eq!(HEADER_SIZE + 12, data.len());
+        assert_eq!(
+            vec![1, 0, 2, 1, 1, 2, 1, 1, 1, 0, 0, 0],
+            data[HEADER_SIZE..].to_vec()
+        );
+    }
+
+    #[test]
+    fn programs_can_be_read_by_index() {
+        let mut reader =
+            DatabaseReader::with_states(database(&["1R1 1L1 1L0   H", "1R1   H 0L0 1R1"]), 2)
+                .unwrap();
+
+        assert_eq!(2, reader.len());
+        assert_eq!("1R1   H 0L0 1R1", reader.get(1).unwrap().to_string());
+        assert_eq!(
+            "1R1 1L1 1L0 ???",
+            reader.get_incomplete(0).unwrap().to_string()
+        );
+        assert!(matches!(reader.get(2), Err(Error::OutOfRange(2))));
+    }
+
+    #[test]
+    fn programs_can_be_streamed_by_range() {
+        let mut reader = DatabaseReader::with_states(
+            database(&["1R1 1L1 1L0   H", "1R1   H 0L0 1R1", "1L1   H 1R0 1L1"]),
+            2,
+        )
+        .unwrap();
+
+        let actual: Vec<String> = reader
+            .programs(1..3)
+            .map(|program| program.unwrap().to_string())
+            .collect();
+
+        assert_eq!(vec!["1R1   H 0L0 1R1", "1L1   H 1R0 1L1"], actual);
+    }
+
+    #[test]
+    fn headers_of_filtered_databases_are_corrected_when_finished() {
+        let mut writer =
+            DatabaseWriter::with_states(Cursor::new(Vec::new()), &Header::with_total(0), 2)
+                .unwrap();
+        for description in &["1R1 1L1 1L0   H", "1R1   H 0L0 1R1"] {
+            let program: CompleteProgram = description.parse().unwrap();
+            writer.write(&program).unwrap();
+        }
+        let output = writer.finish().unwrap();
+
+        let mut reader = DatabaseReader::with_states(output, 2).unwrap();
+        assert_eq!(
+            &Header {
+                undecided_time: 0,
+                undecided_space: 0,
+                total: 2,
+                sorted: false
+            },
+            reader.header()
+        );
+        assert_eq!("1R1   H 0L0 1R1", reader.get(1).unwrap().to_string());
+    }
+
+    #[test]
+    fn undefined_transitions_must_be_all_zeros() {
+        let mut data = database(&["1R1 1L1 1L0   H"]).into_inner();
+        data[HEADER_SIZE + 9] = 1;
+        let mut reader = DatabaseReader::with_states(Cursor::new(data), 2).unwrap();
+
+        assert!(matches!(
+            reader.get(0),
+            Err(Error::UndefinedTransition(1, 0))
+        ));
+    }
+
+    #[test]
+    fn programs_with_more_than_two_symbols_are_rejected() {
+        let mut output = Vec::new();
+        let mut writer =
+            DatabaseWriter::with_states(&mut output, &Header::with_total(1), 2).unwrap();
+        let program = CompleteProgram::parse_as("1RB2LB0RA_1LA1RZ2LA", Format::Standard).unwrap();
+
+        assert!(matches!(
+            writer.write(&program),
+            Err(Error::UnsupportedSymbols(3))
+        ));
+    }
+
+    #[test]
+    fn streams_end_after_a_partial_record() {
+        let mut data = database(&["1R1 1L1 1L0   H", "1R1   H 0L0 1R1"]).into_inner();
+        data.truncate(HEADER_SIZE + 12 + 5);
+        let mut reader = DatabaseReader::with_states(Cursor::new(data), 2).unwrap();
+
+        let mut records = reader.programs(0..2);
+
+        assert!(matches!(records.next(), Some(Ok(_))));
+        assert!(matches!(records.next(), Some(Err(Error::Io(_)))));
+        assert!(records.next().is_none());
+    }
+}
@@ -0,0 +1,188 @@
+//! Decide that a Turing machine never halts by reasoning backwards from its halting transitions.
+//!
+//! A Turing machine that halts, does so by reading a key with a halting action. Starting from that key, one can ask which
+//! configurations could have lead to it. A configuration is only partially known: the state, the position of the tape head
+//! and the cells that the steps under consideration read. A predecessor of such a configuration moved the tape head onto its
+//! current position, so it wrote a symbol on the neighbouring cell. When that contradicts what is known about that cell, the
+//! predecessor is impossible.
+//!
+//! When every chain of predecessors dies out without passing a configuration that could be the start of a run, i.e. state `0`
+//! on a blank tape, none of the halting transitions is ever taken.
+use crate::decider::Decider;
+use crate::turing::{Action, Assessment, CompleteProgram, Details, Direction, Key, State, Symbol};
+use std::collections::HashMap;
+
+/// Reasons backwards from the halting transitions of a program, for at most a certain depth.
+pub struct BackwardReasoning {
+    depth: usize,
+}
+
+/// The conclusion of reasoning backwards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Backward {
+    /// No configuration leads to a halting transition. All chains of predecessors died out within `depth` steps.
+    NonHalting {
+        /// The length of the longest chain of predecessors
+        depth: usize,
+    },
+    /// A chain of predecessors could be the start of a run, or was still alive at the maximum depth.
+    Inconclusive,
+}
+
+impl BackwardReasoning {
+    /// Create a decider that follows chains of predecessors for at most `depth` steps.
+    pub fn new(depth: usize) -> Self {
+        Self { depth }
+    }
+
+    /// Reason backwards from all halting transitions of a program.
+    pub fn decide(&self, program: &CompleteProgram) -> Backward {
+        let mut deepest = 0;
+        for (key, action) in program {
+            if action.halts() {
+                match self.decide_key(program, key) {
+                    Backward::NonHalting { depth } => deepest = deepest.max(depth),
+                    Backward::Inconclusive => return Backward::Inconclusive,
+                }
+            }
+        }
+        Backward::NonHalting { depth: deepest }
+    }
+
+    /// Reason backwards from a single key of a program.
+    pub fn decide_key(&self, program: &CompleteProgram, key: Key) -> Backward {
+        let mut deepest = 0;
+        let mut configurations = vec![Configuration::at(key)];
+        while let Some(configuration) = configurations.pop() {
+            if configuration.could_start() || configuration.depth >= self.depth {
+                return Backward::Inconclusive;
+            }
+            deepest = deepest.max(configuration.depth);
+            configurations.extend(configuration.predecessors(program));
+        }
+        Backward::NonHalting { depth: deepest }
+    }
+
+    /// Refine the assessment of a Turing machine that did not halt within its maximum number of steps.
+    pub fn assess(&self, program: &CompleteProgram, assessment: Assessment) -> Assessment {
+        match assessment {
+            Assessment::NotHalted(details) => match self.decide(program) {
+                Backward::NonHalting { depth } => Assessment::BackwardRefuted { depth, details },
+                Backward::Inconclusive => assessment,
+            },
+            _ => assessment,
+        }
+    }
+}
+
+impl Decider for BackwardReasoning {
+    fn name(&self) -> &'static str {
+        "backward reasoning"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        match self.decide(program) {
+            Backward::NonHalting { depth } => Some(Assessment::BackwardRefuted {
+                depth,
+                details: Details::unsimulated(program),
+            }),
+            Backward::Inconclusive => None,
+        }
+    }
+}
+
+/// A partially known configuration of a Turing machine.
+struct Configuration {
+    depth: usize,
+    state: State,
+    head: i128,
+    tape: HashMap<i128, Symbol>,
+}
+
+impl Configuration {
+    fn at(key: Key) -> Self {
+        let mut tape = HashMap::new();
+        tape.insert(0, key.symbol);
+        Self {
+            depth: 0,
+            state: key.state,
+            head: 0,
+            tape,
+        }
+    }
+
+    fn could_start(&self) -> bool {
+        self.state == State::Number(0) && self.tape.values().all(|symbol| *symbol == Symbol::BLANK)
+    }
+
+    fn predecessors(&self, program: &CompleteProgram) -> Vec<Configuration> {
+        program
+            .into_iter()
+            .filter_map(|(key, action)| match action {
+                Action::Do {
+                    symbol,
+                    direction,
+                    state,
+                } if state == self.state => {
+                    let head = match direction {
+                        Direction::Left => self.head + 1,
+                        Direction::Right => self.head - 1,
+                    };
+                    if self.tape.get(&head).is_none_or(|known| *known == symbol) {
+                        let mut tape = self.tape.clone();
+                        tape.insert(head, key.symbol);
+                        Some(Configuration {
+                            depth: self.depth + 1,
+                            state: key.state,
+                            head,
+                            tape,
+                        })
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decide(program: &str) -> Backward {
+        let program: CompleteProgram = program.parse().unwrap();
+        BackwardReasoning::new(20).decide(&program)
+    }
+
+    #[test]
+    fn programs_without_halting_transitions_do_not_halt() {
+        assert_eq!(Backward::NonHalting { depth: 0 }, decide("1R0 1L0 1R0 1L0"));
+    }
+
+    #[test]
+    fn unreachable_halting_transitions_are_refuted() {
+        // Every chain of predecessors contradicts what is known about the tape within two steps.
+        assert_eq!(Backward::NonHalting { depth: 2 }, decide("1R1 0L0 1L0   H"));
+    }
+
+    #[test]
+    fn halting_programs_are_inconclusive() {
+        assert_eq!(Backward::Inconclusive, decide("1R1 1L1 1L0   H"));
+        assert_eq!(Backward::Inconclusive, decide("1R1 1L1 1L0 1LH"));
+    }
+
+    #[test]
+    fn decisions_agree_with_running_machines() {
+        use crate::turing::{Machine, Programs, SimpleTape};
+
+        let reasoning = BackwardReasoning::new(10);
+        for program in Programs::all(2) {
+            if let Backward::NonHalting { .. } = reasoning.decide(&program) {
+                let mut machine = Machine::new(SimpleTape::empty(), &program);
+                assert!(!matches!(machine.run(100), Assessment::HaltedIn(_)));
+            }
+        }
+    }
+}
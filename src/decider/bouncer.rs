@@ -0,0 +1,559 @@
+//! Detect Turing machines that bounce back and forth over a tape that grows linearly.
+//!
+//! A *bouncer* sweeps over a region of the tape that grows by the same word every sweep. Take the configurations at which
+//! the tape head breaks a record, in the same direction and in the same state. When three consecutive such configurations
+//! only differ by inserting the same word `w` at the same place, the tape is guessed to follow the formula `u w^(n + c) v`.
+//!
+//! The guess is proven by running the formula symbolically, for an arbitrary `n`. Outside of the repeated word the machine is
+//! run as usual. When the tape head enters the repeated word, the machine is run on a single copy of `w`. If it leaves that
+//! copy on the other side in the same state, it does the same for every copy, so all `n + c` copies are crossed at once.
+//! Otherwise a single copy is split off, which needs `c` to be positive. Once the symbolic run reaches the formula for `n + 1`
+//! the machine repeats this forever, so it never halts.
+use crate::decider::{Certificate, Decider};
+use crate::turing::{
+    Action, Assessment, CompleteProgram, Details, Direction, Lookup, Machine, Program, Progress,
+    SimpleTape, State, Symbol, Tape,
+};
+use std::collections::HashMap;
+
+/// The maximum number of steps a single copy of the repeated word is run for.
+const COPY_LIMIT: usize = 1_000;
+
+/// The number of earlier records that are tried as the middle of three records.
+const CANDIDATES: usize = 4;
+
+/// Runs a Turing machine while remembering its records, in order to detect a bouncer.
+pub struct BouncerDetector {
+    maximum: u128,
+}
+
+/// A certificate that a Turing machine is a bouncer.
+///
+/// After `steps` steps the machine is in `state`, with a tape `prefix repeater^offset suffix`. The tape head is on the cell with
+/// index `head` of `prefix suffix`. From the configuration with tape `prefix repeater^(n + offset) suffix` the machine reaches the
+/// configuration with tape `prefix repeater^(n + 1 + offset) suffix` for every `n`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Bouncer {
+    /// The number of steps before the formula applies
+    pub steps: u128,
+    /// The state of the machine whenever the formula applies
+    pub state: State,
+    /// The symbols to the left of the repeated word
+    pub prefix: Vec<Symbol>,
+    /// The repeated word, which the tape grows by every sweep
+    pub repeater: Vec<Symbol>,
+    /// The number of repetitions after `steps` steps
+    pub offset: usize,
+    /// The symbols to the right of the repeated word
+    pub suffix: Vec<Symbol>,
+    /// The index of the tape head in `prefix suffix`
+    pub head: usize,
+}
+
+impl BouncerDetector {
+    /// Create a detector that runs a Turing machine for at most `maximum` steps.
+    pub fn new(maximum: u128) -> Self {
+        Self { maximum }
+    }
+
+    /// Run the Turing machine until it halts, is found to be a bouncer or reached the maximum number of steps.
+    pub fn run<T>(&self, machine: &mut Machine<T>) -> Assessment
+    where
+        T: Tape,
+    {
+        self.prove(machine).0
+    }
+
+    /// Run the Turing machine like `run`, returning the formula that the tape follows when it is found to be a bouncer.
+    pub fn prove<T>(&self, machine: &mut Machine<T>) -> (Assessment, Option<Bouncer>)
+    where
+        T: Tape,
+    {
+        let mut records: HashMap<(bool, State), Vec<Record>> = HashMap::new();
+        let mut highest = 0;
+        let mut lowest = 0;
+        let mut steps: u128 = 0;
+        while !machine.state().halted() && steps < self.maximum {
+            match machine.step() {
+                Progress::Made => steps += 1,
+                progress => {
+                    return (
+                        Assessment::NoProgress(progress, Details::from_machine(machine, steps, 0)),
+                        None,
+                    )
+                }
+            }
+            let position = machine.tape().position();
+            if position > highest || position < lowest {
+                highest = highest.max(position);
+                lowest = lowest.min(position);
+                let group = records
+                    .entry((position == highest, machine.state()))
+                    .or_default();
+                let current = Record::of(steps, machine);
+                if let Some(certificate) = guess(group, &current)
+                    .into_iter()
+                    .find(|(formula, period)| formula.proven(machine.program(), *period))
+                    .map(|(formula, _)| formula.certificate(steps))
+                {
+                    return (
+                        Assessment::Bounces(Details::from_machine(machine, steps, 0)),
+                        Some(certificate),
+                    );
+                }
+                group.push(current);
+            }
+        }
+        if machine.state().halted() {
+            (
+                Assessment::HaltedIn(Details::from_machine(machine, steps, machine.score())),
+                None,
+            )
+        } else {
+            (
+                Assessment::NotHalted(Details::from_machine(machine, steps, 0)),
+                None,
+            )
+        }
+    }
+}
+
+impl Decider for BouncerDetector {
+    fn name(&self) -> &'static str {
+        "bouncer"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        let mut machine = Machine::new(SimpleTape::empty(), program);
+        Some(self.run(&mut machine))
+    }
+
+    fn certify(&self, program: &CompleteProgram) -> Option<(Assessment, Option<Certificate>)> {
+        let mut machine = Machine::new(SimpleTape::empty(), program);
+        let (assessment, bouncer) = self.prove(&mut machine);
+        Some((assessment, bouncer.map(Certificate::Bouncer)))
+    }
+}
+
+/// The configuration of a Turing machine at a record.
+struct Record {
+    steps: u128,
+    state: State,
+    tape: Vec<Symbol>,
+    head: usize,
+}
+
+impl Record {
+    fn of<T>(steps: u128, machine: &Machine<T>) -> Self
+    where
+        T: Tape,
+    {
+        let snapshot = machine.tape().snapshot();
+        let head = snapshot.left.len();
+        let mut tape: Vec<Symbol> = snapshot.left.into_iter().rev().collect();
+        if snapshot.right.is_empty() {
+            tape.push(Symbol::BLANK);
+        } else {
+            tape.extend(snapshot.right);
+        }
+        Self {
+            steps,
+            state: machine.state(),
+            tape,
+            head,
+        }
+    }
+}
+
+/// Guess formulas from earlier records in the same group and the current record, together with the number of steps between
+/// the last two records.
+fn guess(group: &[Record], current: &Record) -> Vec<(Formula, u128)> {
+    let mut formulas = Vec::new();
+    for middle in group.iter().rev().take(CANDIDATES) {
+        if middle.tape.len() >= current.tape.len() {
+            continue;
+        }
+        let growth = current.tape.len() - middle.tape.len();
+        let first = group
+            .iter()
+            .rev()
+            .find(|record| record.tape.len() + growth == middle.tape.len());
+        if let Some(first) = first {
+            if let Some(formula) = insertion(first, middle, current, growth) {
+                formulas.push((formula, current.steps - middle.steps));
+            }
+        }
+    }
+    formulas
+}
+
+/// Find a word of length `growth` that is inserted at the same place in `first` to get `middle`, and twice to get `last`.
+fn insertion(first: &Record, middle: &Record, last: &Record, growth: usize) -> Option<Formula> {
+    let length = first.tape.len();
+    let common_prefix = first
+        .tape
+        .iter()
+        .zip(middle.tape.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let common_suffix = first
+        .tape
+        .iter()
+        .rev()
+        .zip(middle.tape.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let from = length.saturating_sub(common_suffix);
+    let to = common_prefix.min(length);
+    (from..=to).find_map(|index| {
+        let word = &middle.tape[index..index + growth];
+        let shifted = |head: usize, times: usize| {
+            if head < index {
+                head
+            } else {
+                head + times * growth
+            }
+        };
+        let mut expected = first.tape[..index].to_vec();
+        expected.extend_from_slice(word);
+        expected.extend_from_slice(word);
+        expected.extend_from_slice(&first.tape[index..]);
+        if middle.head == shifted(first.head, 1)
+            && last.head == shifted(first.head, 2)
+            && last.tape == expected
+        {
+            let head = if first.head < index {
+                Head::Prefix(first.head)
+            } else {
+                Head::Suffix(first.head - index)
+            };
+            let mut formula = Formula {
+                state: last.state,
+                prefix: first.tape[..index].to_vec(),
+                word: word.to_vec(),
+                offset: 2,
+                suffix: first.tape[index..].to_vec(),
+                head,
+            };
+            formula.normalize();
+            Some(formula)
+        } else {
+            None
+        }
+    })
+}
+
+/// Where the tape head is in a formula.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Head {
+    Prefix(usize),
+    Suffix(usize),
+}
+
+/// A configuration with tape `prefix word^(n + offset) suffix`, for an arbitrary `n`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Formula {
+    state: State,
+    prefix: Vec<Symbol>,
+    word: Vec<Symbol>,
+    offset: usize,
+    suffix: Vec<Symbol>,
+    head: Head,
+}
+
+impl Formula {
+    /// Prove that this formula reaches itself with one more repetition of the word, in about `period` symbolic steps.
+    fn proven(&self, program: &dyn Program, period: u128) -> bool {
+        let limit = 2 * period + 16;
+        let mut target = self.clone();
+        target.offset += 1;
+        let mut current = self.clone();
+        for _ in 0..limit {
+            if !current.step(program) {
+                return false;
+            }
+            current.normalize();
+            if current == target {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn certificate(&self, steps: u128) -> Bouncer {
+        Bouncer {
+            steps,
+            state: self.state,
+            prefix: self.prefix.clone(),
+            repeater: self.word.clone(),
+            offset: self.offset,
+            suffix: self.suffix.clone(),
+            head: match self.head {
+                Head::Prefix(index) => index,
+                Head::Suffix(index) => self.prefix.len() + index,
+            },
+        }
+    }
+
+    /// Take a single symbolic step. Returns `false` when the step can not be taken for every `n`.
+    fn step(&mut self, program: &dyn Program) -> bool {
+        let cell = match self.head {
+            Head::Prefix(index) => &mut self.prefix[index],
+            Head::Suffix(index) => &mut self.suffix[index],
+        };
+        let (symbol, direction, state) = match program.lookup(&(self.state, *cell).into()) {
+            Lookup::Determined(Action::Do {
+                symbol,
+                direction,
+                state: state @ State::Number(_),
+            }) => (symbol, direction, state),
+            _ => return false,
+        };
+        *cell = symbol;
+        self.state = state;
+        match (self.head, direction) {
+            (Head::Prefix(0), Direction::Left) => {
+                self.prefix.insert(0, Symbol::BLANK);
+            }
+            (Head::Prefix(index), Direction::Left) => self.head = Head::Prefix(index - 1),
+            (Head::Suffix(index), Direction::Right) => {
+                if index + 1 == self.suffix.len() {
+                    self.suffix.push(Symbol::BLANK);
+                }
+                self.head = Head::Suffix(index + 1);
+            }
+            (Head::Suffix(0), Direction::Left) => return self.cross(program, Direction::Left),
+            (Head::Suffix(index), Direction::Left) => self.head = Head::Suffix(index - 1),
+            (Head::Prefix(index), Direction::Right) => {
+                if index + 1 < self.prefix.len() {
+                    self.head = Head::Prefix(index + 1);
+                } else {
+                    return self.cross(program, direction);
+                }
+            }
+        }
+        true
+    }
+
+    /// Cross the repeated word in a certain direction, either all copies at once or by splitting off a single copy.
+    fn cross(&mut self, program: &dyn Program, direction: Direction) -> bool {
+        if let Some(word) = shift(program, &self.word, self.state, direction) {
+            self.word = word;
+            match direction {
+                Direction::Right => {
+                    if self.suffix.is_empty() {
+                        self.suffix.push(Symbol::BLANK);
+                    }
+                    self.head = Head::Suffix(0);
+                }
+                Direction::Left => {
+                    if self.prefix.is_empty() {
+                        self.prefix.push(Symbol::BLANK);
+                    }
+                    self.head = Head::Prefix(self.prefix.len() - 1);
+                }
+            }
+            true
+        } else if self.offset > 0 {
+            self.offset -= 1;
+            match direction {
+                Direction::Right => {
+                    self.head = Head::Prefix(self.prefix.len());
+                    self.prefix.extend_from_slice(&self.word);
+                }
+                Direction::Left => {
+                    let mut suffix = self.word.clone();
+                    suffix.append(&mut self.suffix);
+                    self.suffix = suffix;
+                    self.head = Head::Suffix(self.word.len() - 1);
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Bring the formula in a normal form, so that equal tapes have equal formulas.
+    ///
+    /// Blanks at the far ends are removed, copies of the word next to the repetition are absorbed and the repetition is
+    /// moved as far to the left as possible. Before that the repetition is moved as far to the right as possible, so that blanks
+    /// it passes at the far left end are removed as well.
+    fn normalize(&mut self) {
+        self.trim();
+        while !self.suffix.is_empty()
+            && self.suffix.first() == self.word.first()
+            && self.head != Head::Suffix(0)
+        {
+            let symbol = self.suffix.remove(0);
+            self.word.rotate_left(1);
+            self.prefix.push(symbol);
+            if let Head::Suffix(index) = self.head {
+                self.head = Head::Suffix(index - 1);
+            }
+        }
+        self.trim();
+        let length = self.word.len();
+        loop {
+            if self.suffix.starts_with(&self.word)
+                && !matches!(self.head, Head::Suffix(index) if index < length)
+            {
+                self.suffix.drain(..length);
+                self.offset += 1;
+                if let Head::Suffix(index) = self.head {
+                    self.head = Head::Suffix(index - length);
+                }
+            } else if self.prefix.ends_with(&self.word)
+                && !matches!(self.head, Head::Prefix(index) if index + length >= self.prefix.len())
+            {
+                self.prefix.truncate(self.prefix.len() - length);
+                self.offset += 1;
+            } else if self.prefix.last() == self.word.last()
+                && self.head != Head::Prefix(self.prefix.len() - 1)
+            {
+                let symbol = self.prefix.pop().unwrap();
+                self.word.rotate_right(1);
+                self.suffix.insert(0, symbol);
+                if let Head::Suffix(index) = self.head {
+                    self.head = Head::Suffix(index + 1);
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Remove the blanks at the far ends of the tape.
+    fn trim(&mut self) {
+        while self.prefix.first() == Some(&Symbol::BLANK) && self.head != Head::Prefix(0) {
+            self.prefix.remove(0);
+            if let Head::Prefix(index) = self.head {
+                self.head = Head::Prefix(index - 1);
+            }
+        }
+        while self.suffix.last() == Some(&Symbol::BLANK)
+            && self.head != Head::Suffix(self.suffix.len() - 1)
+        {
+            self.suffix.pop();
+        }
+    }
+}
+
+/// Run the machine on a single copy of a word, entering it in a certain direction. Returns the resulting word when the machine
+/// leaves the copy on the other side, in the same state.
+fn shift(
+    program: &dyn Program,
+    word: &[Symbol],
+    state: State,
+    direction: Direction,
+) -> Option<Vec<Symbol>> {
+    let mut word = word.to_vec();
+    let mut current = state;
+    let mut index: i128 = match direction {
+        Direction::Right => 0,
+        Direction::Left => word.len() as i128 - 1,
+    };
+    for _ in 0..COPY_LIMIT {
+        if index < 0 || index >= word.len() as i128 {
+            let exit = if index < 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+            return if exit == direction && current == state {
+                Some(word)
+            } else {
+                None
+            };
+        }
+        let cell = &mut word[index as usize];
+        match program.lookup(&(current, *cell).into()) {
+            Lookup::Determined(Action::Do {
+                symbol,
+                direction,
+                state: state @ State::Number(_),
+            }) => {
+                *cell = symbol;
+                current = state;
+                index += match direction {
+                    Direction::Left => -1,
+                    Direction::Right => 1,
+                };
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{CompleteProgram, CompoundTape, SimpleTape};
+
+    #[test]
+    fn bouncers_are_detected() {
+        let program: CompleteProgram = "0L1   H 1L2 1R1 1R1 1L2".parse().unwrap();
+        let detector = BouncerDetector::new(1_000);
+
+        for (assessment, certificate) in [
+            detector.prove(&mut Machine::new(SimpleTape::empty(), &program)),
+            detector.prove(&mut Machine::new(CompoundTape::empty(), &program)),
+        ] {
+            assert!(matches!(assessment, Assessment::Bounces(_)));
+            assert_eq!(
+                Some(vec![Symbol::NON_BLANK, Symbol::NON_BLANK]),
+                certificate.map(|bouncer| bouncer.repeater)
+            );
+        }
+    }
+
+    #[test]
+    fn bouncers_can_leave_blanks_behind() {
+        let program: CompleteProgram = "0R1 0L1 1L0 0R2 1R1   H".parse().unwrap();
+        let mut machine = Machine::new(SimpleTape::empty(), &program);
+
+        let assessment = BouncerDetector::new(1_000).run(&mut machine);
+
+        assert!(matches!(assessment, Assessment::Bounces(_)));
+    }
+
+    #[test]
+    fn halting_programs_halt() {
+        for program in ["1R1 1L1 1L0   H", "1R1 1L1 1L0 1LH"] {
+            let program: CompleteProgram = program.parse().unwrap();
+            let mut machine = Machine::new(SimpleTape::empty(), &program);
+
+            let assessment = BouncerDetector::new(1_000).run(&mut machine);
+
+            assert!(matches!(assessment, Assessment::HaltedIn(_)));
+        }
+    }
+
+    #[test]
+    fn formulas_absorb_copies_of_the_word() {
+        // 1 (01)^n 0 1 0 = (10)^(n + 2) 1 0
+        let mut formula = Formula {
+            state: State::Number(0),
+            prefix: vec![Symbol::NON_BLANK, Symbol::BLANK, Symbol::NON_BLANK],
+            word: vec![Symbol::BLANK, Symbol::NON_BLANK],
+            offset: 0,
+            suffix: vec![Symbol::BLANK, Symbol::NON_BLANK, Symbol::BLANK],
+            head: Head::Suffix(2),
+        };
+
+        formula.normalize();
+
+        assert_eq!(
+            Formula {
+                state: State::Number(0),
+                prefix: vec![],
+                word: vec![Symbol::NON_BLANK, Symbol::BLANK],
+                offset: 2,
+                suffix: vec![Symbol::NON_BLANK, Symbol::BLANK],
+                head: Head::Suffix(1),
+            },
+            formula
+        );
+    }
+}
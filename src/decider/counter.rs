@@ -0,0 +1,445 @@
+//! Detect Turing machines that count in binary.
+//!
+//! A binary counter writes a number on the tape, with digits that are encoded by two words of the same width. Every
+//! increment sweeps over the trailing digits, so the tape only grows when the number of digits grows. The steps between the
+//! records of a counter grow exponentially, which is what this decider looks for. Since counters run for a long time on a
+//! short tape, they are best run on a `CompoundTape`, whose run-length encoding keeps long runs of digits cheap.
+//!
+//! Whether a machine counts is proven with a regular language. Runs of digits on the tape are replaced by a *star*, that
+//! stands for any sequence of digits. Starting from a configuration of the machine, all configurations of this language are
+//! explored. When the head moves onto a star, the star is either empty, or starts with one of the digits. Digits next to a star
+//! are absorbed by it. When the exploration closes without reaching a halting transition, no configuration that the machine
+//! can reach halts.
+use crate::decider::{Certificate, Decider};
+use crate::turing::{
+    Action, Assessment, CompleteProgram, CompoundTape, Details, Direction, Lookup, Machine,
+    Program, Progress, State, Symbol, Symbols, Tape,
+};
+use std::collections::HashSet;
+
+/// The maximum number of configurations that is explored for a single pair of digits.
+const CONFIGURATIONS: usize = 10_000;
+
+/// The maximum number of tokens in a configuration that is explored.
+const TOKENS: usize = 64;
+
+/// The widths of the digits that are tried.
+const WIDTHS: [usize; 3] = [1, 2, 3];
+
+/// Runs a Turing machine while remembering when it breaks records, in order to detect a binary counter.
+pub struct CounterDetector {
+    maximum: u128,
+}
+
+/// A certificate that a Turing machine counts.
+///
+/// After `steps` steps, replace every run of at least two `digits` on the tape by any sequence of digits. The language of
+/// these configurations is closed under taking a step, and contains `configurations` configurations without halting ones.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Counter {
+    /// The number of steps before the language applies
+    pub steps: u128,
+    /// The words that encode the digits
+    pub digits: (Vec<Symbol>, Vec<Symbol>),
+    /// The number of configurations in the language
+    pub configurations: usize,
+}
+
+impl CounterDetector {
+    /// Create a detector that runs a Turing machine for at most `maximum` steps.
+    pub fn new(maximum: u128) -> Self {
+        Self { maximum }
+    }
+
+    /// Run the Turing machine until it halts, is found to count or reached the maximum number of steps.
+    pub fn run<T>(&self, machine: &mut Machine<T>) -> Assessment
+    where
+        T: Tape,
+    {
+        self.prove(machine).0
+    }
+
+    /// Run the Turing machine like `run`, returning the language that it stays in when it is found to count.
+    pub fn prove<T>(&self, machine: &mut Machine<T>) -> (Assessment, Option<Counter>)
+    where
+        T: Tape,
+    {
+        let mut records: Vec<u128> = vec![0];
+        let mut highest = 0;
+        let mut lowest = 0;
+        let mut steps: u128 = 0;
+        while !machine.state().halted() && steps < self.maximum {
+            match machine.step() {
+                Progress::Made => steps += 1,
+                progress => {
+                    return (
+                        Assessment::NoProgress(progress, Details::from_machine(machine, steps, 0)),
+                        None,
+                    )
+                }
+            }
+            let position = machine.tape().position();
+            if position > highest || position < lowest {
+                highest = highest.max(position);
+                lowest = lowest.min(position);
+                records.push(steps);
+                if machine.program().symbols() == 2 && exponential(&records) {
+                    let start = Abstract::of(machine);
+                    if let Some((digits, configurations)) =
+                        candidates(&start).into_iter().find_map(|digits| {
+                            closed(machine.program(), &start, &digits).map(|n| (digits, n))
+                        })
+                    {
+                        return (
+                            Assessment::Counts(Details::from_machine(machine, steps, 0)),
+                            Some(Counter {
+                                steps,
+                                digits: (digits[0].clone(), digits[1].clone()),
+                                configurations,
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+        if machine.state().halted() {
+            (
+                Assessment::HaltedIn(Details::from_machine(machine, steps, machine.score())),
+                None,
+            )
+        } else {
+            (
+                Assessment::NotHalted(Details::from_machine(machine, steps, 0)),
+                None,
+            )
+        }
+    }
+}
+
+impl Decider for CounterDetector {
+    fn name(&self) -> &'static str {
+        "counter"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        let mut machine = Machine::new(CompoundTape::empty(), program);
+        Some(self.run(&mut machine))
+    }
+
+    fn certify(&self, program: &CompleteProgram) -> Option<(Assessment, Option<Certificate>)> {
+        let mut machine = Machine::new(CompoundTape::empty(), program);
+        let (assessment, counter) = self.prove(&mut machine);
+        Some((assessment, counter.map(Certificate::Counter)))
+    }
+}
+
+/// Determine if the last gaps between records grow by at least half every time.
+fn exponential(records: &[u128]) -> bool {
+    let gaps: Vec<u128> = records.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    gaps.len() >= 4
+        && gaps[gaps.len() - 4..]
+            .windows(2)
+            .all(|pair| 2 * pair[1] >= 3 * pair[0])
+}
+
+/// A part of a configuration in the language.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+enum Token {
+    /// A known symbol
+    Symbol(Symbol),
+    /// Any sequence of digits
+    Star,
+}
+
+/// A configuration in the language, i.e. a configuration in which parts of the tape are replaced by stars.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+struct Abstract {
+    state: State,
+    tokens: Vec<Token>,
+    head: usize,
+}
+
+impl Abstract {
+    fn of<T>(machine: &Machine<T>) -> Self
+    where
+        T: Tape,
+    {
+        let snapshot = machine.tape().snapshot();
+        let head = snapshot.left.len();
+        let mut symbols: Vec<Symbol> = snapshot.left.into_iter().rev().collect();
+        if snapshot.right.is_empty() {
+            symbols.push(Symbol::BLANK);
+        } else {
+            symbols.extend(snapshot.right);
+        }
+        Self {
+            state: machine.state(),
+            tokens: symbols.into_iter().map(Token::Symbol).collect(),
+            head,
+        }
+    }
+
+    fn symbols(&self, from: usize, width: usize) -> Option<Vec<Symbol>> {
+        if from + width > self.tokens.len() || (from..from + width).contains(&self.head) {
+            return None;
+        }
+        self.tokens[from..from + width]
+            .iter()
+            .map(|token| match token {
+                Token::Symbol(symbol) => Some(*symbol),
+                Token::Star => None,
+            })
+            .collect()
+    }
+
+    /// Replace runs of at least two digits by a star.
+    fn generalize(&self, digits: &[Vec<Symbol>; 2]) -> Self {
+        let width = digits[0].len();
+        let mut result = Self {
+            state: self.state,
+            tokens: Vec::new(),
+            head: 0,
+        };
+        let mut index = 0;
+        while index < self.tokens.len() {
+            let mut end = index;
+            while self
+                .symbols(end, width)
+                .is_some_and(|word| digits.contains(&word))
+            {
+                end += width;
+            }
+            if end - index >= 2 * width {
+                result.tokens.push(Token::Star);
+                index = end;
+            } else {
+                if index == self.head {
+                    result.head = result.tokens.len();
+                }
+                result.tokens.push(self.tokens[index].clone());
+                index += 1;
+            }
+        }
+        result
+    }
+
+    /// Take a single step, splitting stars when needed. Returns `None` when the step can not be taken.
+    fn step(&self, program: &dyn Program, digits: &[Vec<Symbol>; 2]) -> Option<Vec<Abstract>> {
+        let symbol = match self.tokens[self.head] {
+            Token::Symbol(symbol) => symbol,
+            Token::Star => return None,
+        };
+        match program.lookup(&(self.state, symbol).into()) {
+            Lookup::Determined(Action::Do {
+                symbol,
+                direction,
+                state: state @ State::Number(_),
+            }) => {
+                let mut next = self.clone();
+                next.tokens[self.head] = Token::Symbol(symbol);
+                next.state = state;
+                let target = match direction {
+                    Direction::Left => self.head as i128 - 1,
+                    Direction::Right => self.head as i128 + 1,
+                };
+                Some(next.enter(target, direction, digits))
+            }
+            _ => None,
+        }
+    }
+
+    /// Move the head onto the token at `target`, which it reaches moving in `direction`.
+    fn enter(
+        mut self,
+        target: i128,
+        direction: Direction,
+        digits: &[Vec<Symbol>; 2],
+    ) -> Vec<Abstract> {
+        let target = if target < 0 {
+            self.tokens.insert(0, Token::Symbol(Symbol::BLANK));
+            0
+        } else {
+            if target as usize == self.tokens.len() {
+                self.tokens.push(Token::Symbol(Symbol::BLANK));
+            }
+            target as usize
+        };
+        match self.tokens[target] {
+            Token::Symbol(_) => {
+                self.head = target;
+                vec![self]
+            }
+            Token::Star => {
+                let beyond = match direction {
+                    Direction::Left => target as i128 - 1,
+                    Direction::Right => target as i128 + 1,
+                };
+                let mut result = self.clone().enter(beyond, direction, digits);
+                for digit in digits {
+                    let mut split = self.clone();
+                    let symbols = digit.iter().map(|symbol| Token::Symbol(*symbol));
+                    match direction {
+                        Direction::Right => {
+                            split.tokens.splice(target..target, symbols);
+                            split.head = target;
+                        }
+                        Direction::Left => {
+                            split.tokens.splice(target + 1..target + 1, symbols);
+                            split.head = target + digit.len();
+                        }
+                    }
+                    result.push(split);
+                }
+                result
+            }
+        }
+    }
+
+    /// Replace runs of digits by a star, absorb digits next to stars, merge adjacent stars and remove blanks at the far ends.
+    fn normalize(&mut self, digits: &[Vec<Symbol>; 2]) {
+        *self = self.generalize(digits);
+        let width = digits[0].len();
+        let mut index = 0;
+        while index < self.tokens.len() {
+            if self.tokens[index] == Token::Star {
+                if self.tokens.get(index + 1) == Some(&Token::Star) {
+                    self.remove(index + 1, 1);
+                    continue;
+                }
+                if self
+                    .symbols(index + 1, width)
+                    .is_some_and(|word| digits.contains(&word))
+                {
+                    self.remove(index + 1, width);
+                    continue;
+                }
+                if index >= width
+                    && self
+                        .symbols(index - width, width)
+                        .is_some_and(|word| digits.contains(&word))
+                {
+                    self.remove(index - width, width);
+                    index -= width;
+                    continue;
+                }
+            }
+            index += 1;
+        }
+        while self.head > 0 && self.tokens[0] == Token::Symbol(Symbol::BLANK) {
+            self.remove(0, 1);
+        }
+        while self.head + 1 < self.tokens.len()
+            && self.tokens.last() == Some(&Token::Symbol(Symbol::BLANK))
+        {
+            self.tokens.pop();
+        }
+    }
+
+    fn remove(&mut self, from: usize, width: usize) {
+        self.tokens.drain(from..from + width);
+        if self.head >= from + width {
+            self.head -= width;
+        }
+    }
+}
+
+/// The pairs of digits that are worth trying for a configuration.
+fn candidates(start: &Abstract) -> Vec<[Vec<Symbol>; 2]> {
+    let mut result = Vec::new();
+    for width in WIDTHS {
+        let words = words(width);
+        for (index, zero) in words.iter().enumerate() {
+            for one in &words[index + 1..] {
+                let digits = [zero.clone(), one.clone()];
+                if start.generalize(&digits).tokens.contains(&Token::Star) {
+                    result.push(digits);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// All words of a certain width.
+fn words(width: usize) -> Vec<Vec<Symbol>> {
+    (0..width).fold(vec![vec![]], |words, _| {
+        words
+            .into_iter()
+            .flat_map(|word| {
+                Symbols::all().map(move |symbol| {
+                    let mut longer = word.clone();
+                    longer.push(symbol);
+                    longer
+                })
+            })
+            .collect()
+    })
+}
+
+/// Explore the language of configurations generalized from `start`. Returns the number of configurations when it is closed
+/// and contains no halting configurations.
+fn closed(program: &dyn Program, start: &Abstract, digits: &[Vec<Symbol>; 2]) -> Option<usize> {
+    let mut initial = start.clone();
+    initial.normalize(digits);
+    let mut seen = HashSet::new();
+    let mut todo = vec![initial.clone()];
+    seen.insert(initial);
+    while let Some(configuration) = todo.pop() {
+        for mut next in configuration.step(program, digits)? {
+            next.normalize(digits);
+            if next.tokens.len() > TOKENS {
+                return None;
+            }
+            if seen.insert(next.clone()) {
+                if seen.len() > CONFIGURATIONS {
+                    return None;
+                }
+                todo.push(next);
+            }
+        }
+    }
+    Some(seen.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{CompleteProgram, CompoundTape};
+
+    #[test]
+    fn counters_are_detected() {
+        let program: CompleteProgram = "1L1   H 0R2 0L1 1L1 1R2".parse().unwrap();
+        let mut machine = Machine::new(CompoundTape::empty(), &program);
+
+        let (assessment, certificate) = CounterDetector::new(1_000).prove(&mut machine);
+
+        assert!(matches!(assessment, Assessment::Counts(_)));
+        assert_eq!(
+            Some((vec![Symbol::BLANK], vec![Symbol::NON_BLANK])),
+            certificate.map(|counter| counter.digits)
+        );
+    }
+
+    #[test]
+    fn halting_programs_halt() {
+        for program in ["1R1 1L1 1L0   H", "1R1 1L1 1L0 1LH"] {
+            let program: CompleteProgram = program.parse().unwrap();
+            let mut machine = Machine::new(CompoundTape::empty(), &program);
+
+            let assessment = CounterDetector::new(1_000).run(&mut machine);
+
+            assert!(matches!(assessment, Assessment::HaltedIn(_)));
+        }
+    }
+
+    #[test]
+    fn records_of_counters_grow_exponentially() {
+        assert!(exponential(&[0, 1, 3, 10, 25, 56]));
+        assert!(!exponential(&[0, 12, 24, 40, 60, 84]));
+    }
+
+    #[test]
+    fn words_contain_all_combinations_of_symbols() {
+        assert_eq!(8, words(3).len());
+    }
+}
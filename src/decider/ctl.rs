@@ -0,0 +1,269 @@
+//! Decide that a Turing machine never halts with a closed tape language.
+//!
+//! A *closed tape language* is a set of configurations that contains the start configuration, that is closed under taking a
+//! step and that does not contain halting configurations. Once such a language is found, the machine never halts.
+//!
+//! The languages considered here are described by windows of `k` symbols. Read the tape outward from the head, to the left
+//! and to the right. A configuration belongs to the language when every window of `k` consecutive symbols on the left is one
+//! of the `left` windows, every window on the right is one of the `right` windows and the state, together with the `k` symbols
+//! nearest to the head on either side and the symbol under the head, is one of the `local` configurations. The windows are the
+//! transitions of a [De Bruijn automaton](https://en.wikipedia.org/wiki/De_Bruijn_graph) that recognizes the halves of the
+//! tape, so the language is regular.
+//!
+//! The smallest language of this form is found by a fixpoint computation, starting from the blank tape.
+use crate::decider::{Certificate, Decider};
+use crate::turing::{
+    Action, Assessment, CompleteProgram, Details, Direction, Lookup, Program, State, Symbol,
+    Symbols,
+};
+use std::collections::HashSet;
+
+/// Searches for a closed tape language with windows of increasing size.
+pub struct ClosedTapeLanguage {
+    maximum: usize,
+}
+
+/// A certificate that a Turing machine never halts, in the form of a closed tape language.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Language {
+    /// The size of the windows
+    pub k: usize,
+    /// The windows that can occur to the left of the head, read outward
+    pub left: HashSet<Vec<Symbol>>,
+    /// The windows that can occur to the right of the head, read outward
+    pub right: HashSet<Vec<Symbol>>,
+    /// The configurations near the head that can occur
+    pub local: HashSet<Local>,
+}
+
+/// The configuration of a Turing machine near its head.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Local {
+    /// The state of the machine
+    pub state: State,
+    /// The `k` symbols to the left of the head, nearest first
+    pub left: Vec<Symbol>,
+    /// The symbol under the head
+    pub head: Symbol,
+    /// The `k` symbols to the right of the head, nearest first
+    pub right: Vec<Symbol>,
+}
+
+impl ClosedTapeLanguage {
+    /// Create a decider that tries windows of size up to `maximum`.
+    pub fn new(maximum: usize) -> Self {
+        Self { maximum }
+    }
+
+    /// Search a closed tape language for a program. Returns `None` when none is found.
+    ///
+    /// Only programs over an alphabet of two symbols are considered.
+    pub fn decide(&self, program: &CompleteProgram) -> Option<Assessment> {
+        self.prove(program).map(|(assessment, _)| assessment)
+    }
+
+    /// Search a closed tape language for a program like `decide`, returning the language that was found.
+    pub fn prove(&self, program: &CompleteProgram) -> Option<(Assessment, Language)> {
+        if program.symbols() != 2 {
+            return None;
+        }
+        (1..=self.maximum)
+            .find_map(|k| Language::search(program, k))
+            .map(|language| {
+                (
+                    Assessment::ClosedLanguage(Details::unsimulated(program)),
+                    language,
+                )
+            })
+    }
+}
+
+impl Decider for ClosedTapeLanguage {
+    fn name(&self) -> &'static str {
+        "closed tape language"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        self.decide(program)
+    }
+
+    fn certify(&self, program: &CompleteProgram) -> Option<(Assessment, Option<Certificate>)> {
+        self.prove(program).map(|(assessment, language)| {
+            (assessment, Some(Certificate::Language(Box::new(language))))
+        })
+    }
+}
+
+impl Language {
+    fn start(k: usize) -> Self {
+        let blank = vec![Symbol::BLANK; k];
+        let mut language = Self {
+            k,
+            left: HashSet::new(),
+            right: HashSet::new(),
+            local: HashSet::new(),
+        };
+        language.left.insert(blank.clone());
+        language.right.insert(blank.clone());
+        language.local.insert(Local {
+            state: State::Number(0),
+            left: blank.clone(),
+            head: Symbol::BLANK,
+            right: blank,
+        });
+        language
+    }
+
+    /// Find the smallest language with windows of size `k` that is closed, if it does not contain halting configurations.
+    fn search(program: &dyn Program, k: usize) -> Option<Self> {
+        let mut language = Self::start(k);
+        loop {
+            let mut changed = false;
+            for local in language.local.clone() {
+                let (window, successors) = language.successors(program, &local)?;
+                changed |= match window {
+                    Window::Left(window) => language.left.insert(window),
+                    Window::Right(window) => language.right.insert(window),
+                };
+                for successor in successors {
+                    changed |= language.local.insert(successor);
+                }
+            }
+            if !changed {
+                return Some(language);
+            }
+        }
+    }
+
+    /// Check independently that this language contains the start configuration, is closed and does not contain halting
+    /// configurations.
+    pub fn check(&self, program: &dyn Program) -> bool {
+        let start = Self::start(self.k);
+        start.left.is_subset(&self.left)
+            && start.right.is_subset(&self.right)
+            && start.local.is_subset(&self.local)
+            && self.local.iter().all(|local| {
+                local.left.len() == self.k
+                    && local.right.len() == self.k
+                    && match self.successors(program, local) {
+                        Some((window, successors)) => {
+                            let known = match window {
+                                Window::Left(window) => self.left.contains(&window),
+                                Window::Right(window) => self.right.contains(&window),
+                            };
+                            known
+                                && successors
+                                    .iter()
+                                    .all(|successor| self.local.contains(successor))
+                        }
+                        None => false,
+                    }
+            })
+    }
+
+    /// The window that a step from a local configuration adds, together with the local configurations it can lead to. Returns
+    /// `None` when the step halts.
+    fn successors(&self, program: &dyn Program, local: &Local) -> Option<(Window, Vec<Local>)> {
+        let (symbol, direction, state) = match program.lookup(&(local.state, local.head).into()) {
+            Lookup::Determined(Action::Do {
+                symbol,
+                direction,
+                state: state @ State::Number(_),
+            }) => (symbol, direction, state),
+            _ => return None,
+        };
+        let (behind, ahead, windows) = match direction {
+            Direction::Right => (&local.left, &local.right, &self.right),
+            Direction::Left => (&local.right, &local.left, &self.left),
+        };
+        let mut grown = vec![symbol];
+        grown.extend_from_slice(&behind[..self.k - 1]);
+        let successors = Symbols::all()
+            .map(|next| {
+                let mut shrunk = ahead[1..].to_vec();
+                shrunk.push(next);
+                shrunk
+            })
+            .filter(|shrunk| windows.contains(shrunk))
+            .map(|shrunk| match direction {
+                Direction::Right => Local {
+                    state,
+                    left: grown.clone(),
+                    head: ahead[0],
+                    right: shrunk,
+                },
+                Direction::Left => Local {
+                    state,
+                    left: shrunk,
+                    head: ahead[0],
+                    right: grown.clone(),
+                },
+            })
+            .collect();
+        let window = match direction {
+            Direction::Right => Window::Left(grown),
+            Direction::Left => Window::Right(grown),
+        };
+        Some((window, successors))
+    }
+}
+
+/// A window on either side of the head.
+enum Window {
+    Left(Vec<Symbol>),
+    Right(Vec<Symbol>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{Machine, Programs, SimpleTape};
+
+    #[test]
+    fn closed_languages_are_found_for_non_halting_programs() {
+        let program: CompleteProgram = "1L1   H 0R2 0L1 1L1 1R2".parse().unwrap();
+
+        let (assessment, language) = ClosedTapeLanguage::new(3)
+            .prove(&program)
+            .expect("a closed language");
+
+        assert!(matches!(assessment, Assessment::ClosedLanguage(_)));
+        assert!(language.check(&program));
+    }
+
+    #[test]
+    fn halting_programs_have_no_closed_language() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+
+        assert_eq!(None, ClosedTapeLanguage::new(3).decide(&program));
+    }
+
+    #[test]
+    fn programs_that_write_before_halting_have_no_closed_language() {
+        let program: CompleteProgram = "1R1 1L1 1L0 1LH".parse().unwrap();
+
+        assert_eq!(None, ClosedTapeLanguage::new(3).decide(&program));
+    }
+
+    #[test]
+    fn certificates_that_are_not_closed_are_rejected() {
+        let program: CompleteProgram = "1R0 1L0 1R0 1L0".parse().unwrap();
+        let mut language = Language::search(&program, 2).unwrap();
+
+        let start = Language::start(2).local;
+        language.local.retain(|local| !start.contains(local));
+
+        assert!(!language.check(&program));
+    }
+
+    #[test]
+    fn decisions_agree_with_running_machines() {
+        let decider = ClosedTapeLanguage::new(2);
+        for program in Programs::all(2) {
+            if decider.decide(&program).is_some() {
+                let mut machine = Machine::new(SimpleTape::empty(), &program);
+                assert!(!matches!(machine.run(100), Assessment::HaltedIn(_)));
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Detect Turing machines whose configuration repeats.
+//!
+//! The configuration of a Turing machine consists of its state, the position of its tape head and the contents of its tape. A
+//! Turing machine is deterministic, so once a configuration repeats it will repeat forever.
+//!
+//! The cycle is found with [Brent's algorithm](https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm), which only
+//! keeps a single configuration around. Tape contents are only compared when state and head position agree.
+use crate::decider::Decider;
+use crate::turing::{
+    Assessment, CompleteProgram, Details, Machine, Program, Progress, SimpleTape, State, Tape,
+};
+
+/// Runs a Turing machine while comparing its configuration with an earlier one, in order to detect a cycle.
+pub struct CycleDetector {
+    maximum: u128,
+}
+
+impl CycleDetector {
+    /// Create a detector that runs a Turing machine for at most `maximum` steps.
+    pub fn new(maximum: u128) -> Self {
+        Self { maximum }
+    }
+
+    /// Run the Turing machine until it halts, cycles or reached the maximum number of steps.
+    pub fn run<T>(&self, machine: &mut Machine<T>) -> Assessment
+    where
+        T: Tape + Clone,
+    {
+        let start = Configuration::of(machine);
+        let mut saved = start.clone();
+        let mut power: u128 = 1;
+        let mut period: u128 = 0;
+        let mut steps: u128 = 0;
+        while !machine.state().halted() && steps < self.maximum {
+            match machine.step() {
+                Progress::Made => steps += 1,
+                progress => {
+                    return Assessment::NoProgress(
+                        progress,
+                        Details::from_machine(machine, steps, 0),
+                    )
+                }
+            }
+            period += 1;
+            if saved.matches(machine) {
+                return Assessment::Cycles {
+                    preperiod: start.preperiod(machine.program(), period),
+                    period,
+                    details: Details::from_machine(machine, steps, 0),
+                };
+            }
+            if period == power {
+                saved = Configuration::of(machine);
+                power *= 2;
+                period = 0;
+            }
+        }
+        if machine.state().halted() {
+            Assessment::HaltedIn(Details::from_machine(machine, steps, machine.score()))
+        } else {
+            Assessment::NotHalted(Details::from_machine(machine, steps, 0))
+        }
+    }
+}
+
+impl Decider for CycleDetector {
+    fn name(&self) -> &'static str {
+        "cycle"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        let mut machine = Machine::new(SimpleTape::empty(), program);
+        Some(self.run(&mut machine))
+    }
+}
+
+/// The configuration of a Turing machine
+#[derive(Clone)]
+struct Configuration<T> {
+    state: State,
+    tape: T,
+}
+
+impl<T> Configuration<T>
+where
+    T: Tape + Clone,
+{
+    fn of(machine: &Machine<T>) -> Self {
+        Self {
+            state: machine.state(),
+            tape: machine.tape().clone(),
+        }
+    }
+
+    fn matches(&self, machine: &Machine<T>) -> bool {
+        self.state == machine.state()
+            && self.tape.position() == machine.tape().position()
+            && self.tape.snapshot() == machine.tape().snapshot()
+    }
+
+    fn resume<'a>(&self, program: &'a dyn Program) -> Machine<'a, T> {
+        Machine::with(self.tape.clone(), self.state, program)
+    }
+
+    /// The number of steps before a machine started in this configuration enters a cycle of length `period`.
+    fn preperiod(&self, program: &dyn Program, period: u128) -> u128 {
+        let mut tortoise = self.resume(program);
+        let mut hare = self.resume(program);
+        for _ in 0..period {
+            hare.step();
+        }
+        let mut preperiod = 0;
+        while !Configuration::of(&tortoise).matches(&hare) {
+            tortoise.step();
+            hare.step();
+            preperiod += 1;
+        }
+        preperiod
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{CompleteProgram, CompoundTape, Footprint, SimpleTape};
+
+    #[test]
+    fn looping_programs_cycle() {
+        let program: CompleteProgram = "1R1 0R1 0L0 1L0".parse().unwrap();
+        let detector = CycleDetector::new(100);
+
+        for assessment in [
+            detector.run(&mut Machine::new(SimpleTape::empty(), &program)),
+            detector.run(&mut Machine::new(CompoundTape::empty(), &program)),
+        ] {
+            assert!(matches!(
+                assessment,
+                Assessment::Cycles {
+                    preperiod: 0,
+                    period: 4,
+                    ..
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn halting_programs_halt() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+        let mut machine = Machine::new(SimpleTape::empty(), &program);
+
+        let assessment = CycleDetector::new(100).run(&mut machine);
+
+        assert_eq!(
+            Assessment::HaltedIn(Details {
+                steps: 6,
+                score: 4,
+                multiplicity: 1,
+                footprint: Footprint {
+                    cells: 4,
+                    leftmost: -2,
+                    rightmost: 1
+                }
+            }),
+            assessment
+        );
+    }
+
+    #[test]
+    fn drifting_programs_do_not_cycle() {
+        let program: CompleteProgram = "1R0 1R0 1R0 1R0".parse().unwrap();
+        let mut machine = Machine::new(SimpleTape::empty(), &program);
+
+        let assessment = CycleDetector::new(100).run(&mut machine);
+
+        assert!(matches!(assessment, Assessment::NotHalted(_)));
+    }
+}
@@ -0,0 +1,402 @@
+//! Decide that a Turing machine never halts with finite automata reduction.
+//!
+//! Finite automata reduction describes a regular language of configurations that contains every configuration that eventually
+//! halts, but does not contain the start configuration. A configuration is written as the tape left of the head, the key of the
+//! machine, i.e. its state and the symbol under the head, and the tape right of the head.
+//!
+//! * A deterministic finite automaton (DFA) reads the left of the tape, starting far away from the head. Because its start state
+//!   loops on a blank, it does not matter how many blanks are read first.
+//! * Depending on the state of the DFA and the key, a nondeterministic finite automaton (NFA) continues reading the right of the
+//!   tape from a set of `entries`. The configuration is in the language when the NFA ends in a state of the acceptance vector.
+//!
+//! The DFAs are enumerated up to a size limit. For each DFA the smallest NFA that makes the language closed under taking a step
+//! backward is computed. The search succeeds when that NFA rejects the start configuration.
+use crate::decider::{Certificate, Decider};
+use crate::turing::{
+    Action, Assessment, CompleteProgram, Details, Direction, Key, Lookup, Program, State, Symbol,
+};
+
+/// Searches for a finite automata reduction with DFAs of increasing size.
+pub struct FiniteAutomataReduction {
+    limit: usize,
+}
+
+/// A certificate that a Turing machine never halts, in the form of a finite automata reduction.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Reduction {
+    /// The transitions of the DFA for each symbol. State `0` is the start state.
+    pub dfa: Vec<[usize; 2]>,
+    /// The transitions of the NFA for each symbol
+    pub nfa: Vec<[Vec<usize>; 2]>,
+    /// The NFA states to continue from, indexed by the DFA state times the number of keys plus the index of the key
+    pub entries: Vec<Vec<usize>>,
+    /// Which NFA states accept
+    pub accepting: Vec<bool>,
+}
+
+impl FiniteAutomataReduction {
+    /// Create a decider that tries DFAs with at most `limit` states.
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// Search a finite automata reduction for a program. Returns `None` when none is found.
+    ///
+    /// Only programs over an alphabet of two symbols are considered.
+    pub fn decide(&self, program: &CompleteProgram) -> Option<Assessment> {
+        self.prove(program).map(|(assessment, _)| assessment)
+    }
+
+    /// Search a finite automata reduction for a program like `decide`, returning the reduction that was found.
+    pub fn prove(&self, program: &CompleteProgram) -> Option<(Assessment, Reduction)> {
+        if program.symbols() != 2 {
+            return None;
+        }
+        let keys = program.into_iter().count();
+        (1..=self.limit)
+            .flat_map(dfas)
+            .find_map(|dfa| Reduction::solve(program, keys, dfa))
+            .map(|reduction| {
+                (
+                    Assessment::Reduced(Details::unsimulated(program)),
+                    reduction,
+                )
+            })
+    }
+}
+
+impl Decider for FiniteAutomataReduction {
+    fn name(&self) -> &'static str {
+        "finite automata reduction"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        self.decide(program)
+    }
+
+    fn certify(&self, program: &CompleteProgram) -> Option<(Assessment, Option<Certificate>)> {
+        self.prove(program).map(|(assessment, reduction)| {
+            (
+                assessment,
+                Some(Certificate::Reduction(Box::new(reduction))),
+            )
+        })
+    }
+}
+
+/// All DFAs with `size` states in which every state can be reached and whose start state loops on a blank.
+///
+/// States are numbered in the order they are first reached, so each DFA occurs only once up to a renaming of its states.
+fn dfas(size: usize) -> Vec<Vec<[usize; 2]>> {
+    fn extend(
+        size: usize,
+        dfa: &mut Vec<[usize; 2]>,
+        slot: usize,
+        highest: usize,
+        result: &mut Vec<Vec<[usize; 2]>>,
+    ) {
+        if slot == 2 * size {
+            if highest + 1 == size {
+                result.push(dfa.clone());
+            }
+            return;
+        }
+        let (state, symbol) = (slot / 2, slot % 2);
+        if state > highest {
+            return;
+        }
+        let targets = if slot == 0 {
+            0..=0
+        } else {
+            0..=(highest + 1).min(size - 1)
+        };
+        for target in targets {
+            dfa[state][symbol] = target;
+            extend(size, dfa, slot + 1, highest.max(target), result);
+        }
+    }
+
+    let mut result = Vec::new();
+    extend(size, &mut vec![[0; 2]; size], 0, 0, &mut result);
+    result
+}
+
+impl Reduction {
+    /// Compute the smallest NFA for a DFA, if it rejects the start configuration.
+    ///
+    /// NFA state `0` accepts every tape. Every other NFA state belongs to a DFA state and a key.
+    fn solve(program: &dyn Program, keys: usize, dfa: Vec<[usize; 2]>) -> Option<Self> {
+        let configurations = dfa.len() * keys;
+        let mut nfa = vec![
+            [
+                vec![false; configurations + 1],
+                vec![false; configurations + 1]
+            ];
+            configurations + 1
+        ];
+        nfa[0] = [
+            singleton(configurations + 1, 0),
+            singleton(configurations + 1, 0),
+        ];
+        let mut entries: Vec<Vec<bool>> = (0..configurations)
+            .map(|configuration| singleton(configurations + 1, configuration + 1))
+            .collect();
+        loop {
+            let mut changed = false;
+            for (configuration, entry) in entries.clone().iter().enumerate() {
+                let state = configuration / keys;
+                let key = Key::from(configuration % keys);
+                match program.lookup(&key) {
+                    Lookup::Determined(Action::Do {
+                        symbol,
+                        direction: Direction::Right,
+                        state: next @ State::Number(_),
+                    }) => {
+                        let left = dfa[state][index(symbol)];
+                        for (read, transitions) in nfa[configuration + 1].iter_mut().enumerate() {
+                            let target = left * keys + key_index(next, read);
+                            changed |= union(transitions, &entries[target]);
+                        }
+                    }
+                    Lookup::Determined(Action::Do {
+                        symbol,
+                        direction: Direction::Left,
+                        state: next @ State::Number(_),
+                    }) => {
+                        let mut reached = entry.clone();
+                        for (previous, transitions) in dfa.iter().enumerate() {
+                            for read in (0..2).filter(|&read| transitions[read] == state) {
+                                let source = previous * keys + key_index(next, read);
+                                for nfa_state in members(&entries[source]) {
+                                    union(&mut reached, &nfa[nfa_state][index(symbol)]);
+                                }
+                            }
+                        }
+                        changed |= union(&mut entries[configuration], &reached);
+                    }
+                    _ => {
+                        changed |= union(
+                            &mut entries[configuration],
+                            &singleton(configurations + 1, 0),
+                        )
+                    }
+                }
+            }
+            if start(members(&entries[0]), |nfa_state| {
+                members(&nfa[nfa_state][0])
+            })
+            .contains(&0)
+            {
+                return None;
+            }
+            if !changed {
+                break;
+            }
+        }
+        Some(Self {
+            dfa,
+            nfa: nfa
+                .iter()
+                .map(|transitions| [members(&transitions[0]), members(&transitions[1])])
+                .collect(),
+            entries: entries.iter().map(|entry| members(entry)).collect(),
+            accepting: (0..=configurations)
+                .map(|nfa_state| nfa_state == 0)
+                .collect(),
+        })
+    }
+
+    /// Check independently that this reduction describes a language that contains every halting configuration, is closed under
+    /// taking a step backward and does not contain the start configuration.
+    pub fn check(&self, program: &dyn Program) -> bool {
+        if !self.well_formed() {
+            return false;
+        }
+        let keys = self.entries.len() / self.dfa.len();
+        let step = |from: &[usize], read: usize| -> Vec<usize> {
+            let mut to: Vec<usize> = from
+                .iter()
+                .flat_map(|&nfa_state| self.nfa[nfa_state][read].iter().copied())
+                .collect();
+            to.sort_unstable();
+            to.dedup();
+            to
+        };
+        let includes = |large: &[usize], small: &[usize]| {
+            small.iter().all(|nfa_state| large.contains(nfa_state))
+        };
+        let closed = self
+            .entries
+            .iter()
+            .enumerate()
+            .all(|(configuration, entry)| {
+                let state = configuration / keys;
+                let key = Key::from(configuration % keys);
+                match program.lookup(&key) {
+                    Lookup::Determined(Action::Do {
+                        symbol,
+                        direction: Direction::Right,
+                        state: next @ State::Number(_),
+                    }) => (0..2).all(|read| {
+                        let target = self.dfa[state][index(symbol)] * keys + key_index(next, read);
+                        includes(&step(entry, read), &self.entries[target])
+                    }),
+                    Lookup::Determined(Action::Do {
+                        symbol,
+                        direction: Direction::Left,
+                        state: next @ State::Number(_),
+                    }) => self.dfa.iter().enumerate().all(|(previous, transitions)| {
+                        (0..2)
+                            .filter(|&read| transitions[read] == state)
+                            .all(|read| {
+                                let source = previous * keys + key_index(next, read);
+                                includes(entry, &step(&self.entries[source], index(symbol)))
+                            })
+                    }),
+                    _ => entry.iter().any(|&nfa_state| {
+                        self.accepting[nfa_state]
+                            && self.nfa[nfa_state][0].contains(&nfa_state)
+                            && self.nfa[nfa_state][1].contains(&nfa_state)
+                    }),
+                }
+            });
+        closed
+            && start(self.entries[0].clone(), |nfa_state| {
+                self.nfa[nfa_state][0].clone()
+            })
+            .iter()
+            .all(|&nfa_state| !self.accepting[nfa_state])
+    }
+
+    fn well_formed(&self) -> bool {
+        let states = self.accepting.len();
+        !self.dfa.is_empty()
+            && self.dfa[0][0] == 0
+            && self
+                .dfa
+                .iter()
+                .flatten()
+                .all(|&target| target < self.dfa.len())
+            && !self.entries.is_empty()
+            && self.entries.len().is_multiple_of(self.dfa.len())
+            && self.nfa.len() == states
+            && self
+                .nfa
+                .iter()
+                .flatten()
+                .chain(self.entries.iter())
+                .flatten()
+                .all(|&nfa_state| nfa_state < states)
+    }
+}
+
+/// The NFA states that can be reached from the `entry` of the start configuration by reading blanks only.
+fn start<F>(mut reached: Vec<usize>, successors: F) -> Vec<usize>
+where
+    F: Fn(usize) -> Vec<usize>,
+{
+    let mut frontier = reached.clone();
+    while let Some(nfa_state) = frontier.pop() {
+        for successor in successors(nfa_state) {
+            if !reached.contains(&successor) {
+                reached.push(successor);
+                frontier.push(successor);
+            }
+        }
+    }
+    reached
+}
+
+fn singleton(size: usize, member: usize) -> Vec<bool> {
+    (0..size).map(|index| index == member).collect()
+}
+
+fn members(set: &[bool]) -> Vec<usize> {
+    (0..set.len()).filter(|&index| set[index]).collect()
+}
+
+fn union(set: &mut [bool], other: &[bool]) -> bool {
+    let mut changed = false;
+    for (member, &included) in set.iter_mut().zip(other) {
+        if included && !*member {
+            *member = true;
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn index(symbol: Symbol) -> usize {
+    symbol.digit() as usize
+}
+
+fn key_index(state: State, read: usize) -> usize {
+    Key::from((state, Symbol::new(read as u8))).idx()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{Machine, Programs, SimpleTape};
+
+    #[test]
+    fn reductions_are_found_for_non_halting_programs() {
+        let program: CompleteProgram = "1L1   H 0R2 0L1 1L1 1R2".parse().unwrap();
+
+        let (assessment, reduction) = FiniteAutomataReduction::new(3)
+            .prove(&program)
+            .expect("a reduction");
+
+        assert!(matches!(assessment, Assessment::Reduced(_)));
+        assert!(reduction.check(&program));
+    }
+
+    #[test]
+    fn halting_programs_have_no_reduction() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+
+        assert_eq!(None, FiniteAutomataReduction::new(3).decide(&program));
+    }
+
+    #[test]
+    fn programs_that_write_before_halting_have_no_reduction() {
+        let program: CompleteProgram = "1R1 1L1 1L0 1LH".parse().unwrap();
+
+        assert_eq!(None, FiniteAutomataReduction::new(3).decide(&program));
+    }
+
+    #[test]
+    fn certificates_that_accept_the_start_are_rejected() {
+        let program: CompleteProgram = "1R0 1L0 1R0 1L0".parse().unwrap();
+        let mut reduction = Reduction::solve(&program, 4, vec![[0, 0]]).unwrap();
+
+        reduction.accepting = vec![true; reduction.accepting.len()];
+
+        assert!(!reduction.check(&program));
+    }
+
+    #[test]
+    fn dfas_are_enumerated_up_to_renaming() {
+        assert_eq!(vec![vec![[0, 0]]], dfas(1));
+        assert_eq!(
+            vec![
+                vec![[0, 1], [0, 0]],
+                vec![[0, 1], [0, 1]],
+                vec![[0, 1], [1, 0]],
+                vec![[0, 1], [1, 1]]
+            ],
+            dfas(2)
+        );
+    }
+
+    #[test]
+    fn decisions_agree_with_running_machines() {
+        let decider = FiniteAutomataReduction::new(2);
+        for program in Programs::all(2) {
+            if decider.decide(&program).is_some() {
+                let mut machine = Machine::new(SimpleTape::empty(), &program);
+                assert!(!matches!(machine.run(100), Assessment::HaltedIn(_)));
+            }
+        }
+    }
+}
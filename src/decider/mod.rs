@@ -0,0 +1,45 @@
+//! Decide that Turing machines never halt.
+//!
+//! Running a Turing machine for a maximum number of steps can show that it halts, but never that it runs forever. A *decider*
+//! recognizes a pattern in the behaviour of a Turing machine that proves that it will not halt.
+//!
+//! The following deciders are available
+//! * `CycleDetector` recognizes Turing machines whose configuration repeats.
+//! * `TranslatedCycler` recognizes Turing machines that repeat a local pattern while drifting into blank tape.
+//! * `BouncerDetector` recognizes Turing machines that bounce back and forth over a tape that grows linearly.
+//! * `CounterDetector` recognizes Turing machines that count in binary.
+//! * `ClosedTapeLanguage` recognizes programs that stay within a regular language of configurations.
+//! * `FiniteAutomataReduction` recognizes programs whose halting configurations form a regular language without the start.
+//! * `BackwardReasoning` recognizes programs whose halting transitions can not be reached.
+//! * `HaltingSegment` reasons backward from the halting transitions within a segment of the tape.
+//! * `Reachability` recognizes programs whose graph does not connect state `0` to a halting transition.
+//! * `QuasihaltDetector` recognizes Turing machines that never enter some of their states again, for the beeping busy beaver.
+//!
+//! Every decider implements the `Decider` trait. A `Pipeline` tries deciders in order, starting with the cheap ones, and records
+//! in a `Verdict` which decider settled a program and the `Certificate` it found, if any. A `Simulation` settles programs that
+//! halt.
+mod backward;
+mod bouncer;
+mod counter;
+mod ctl;
+mod cycle;
+mod far;
+mod pipeline;
+mod quasihalt;
+mod reachability;
+mod segment;
+mod simulation;
+mod translated;
+
+pub use backward::{Backward, BackwardReasoning};
+pub use bouncer::{Bouncer, BouncerDetector};
+pub use counter::{Counter, CounterDetector};
+pub use ctl::{ClosedTapeLanguage, Language, Local};
+pub use cycle::CycleDetector;
+pub use far::{FiniteAutomataReduction, Reduction};
+pub use pipeline::{Certificate, Decider, Pipeline, Verdict};
+pub use quasihalt::QuasihaltDetector;
+pub use reachability::Reachability;
+pub use segment::HaltingSegment;
+pub use simulation::Simulation;
+pub use translated::TranslatedCycler;
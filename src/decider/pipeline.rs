@@ -0,0 +1,175 @@
+//! Run deciders one after the other until one of them settles a program.
+use super::{Bouncer, Counter, Language, Reduction};
+use crate::turing::{Assessment, CompleteProgram, Details};
+
+/// Settles the fate of some programs.
+pub trait Decider {
+    /// The name of the decider, used to report which decider settled a program.
+    fn name(&self) -> &'static str;
+
+    /// Assess a program. Returns `None` when the decider can not settle it.
+    ///
+    /// A decider that runs the program and reaches its maximum number of steps assesses it as `NotHalted`, with the details of
+    /// that run.
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment>;
+
+    /// Assess a program, together with the certificate the decider found for it, if any.
+    ///
+    /// Deciders that find certificates override this method; by default the assessment comes without one.
+    fn certify(&self, program: &CompleteProgram) -> Option<(Assessment, Option<Certificate>)> {
+        self.settle(program).map(|assessment| (assessment, None))
+    }
+}
+
+/// The evidence a decider found that a program never halts, beyond its assessment.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Certificate {
+    /// The formula that the tape of a bouncer follows
+    Bouncer(Bouncer),
+    /// The configurations that a counter stays in
+    Counter(Counter),
+    /// A closed tape language
+    Language(Box<Language>),
+    /// A finite automata reduction
+    Reduction(Box<Reduction>),
+}
+
+/// The assessment of a program, together with the decider that settled it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Verdict {
+    /// The name of the decider that settled the program, or `None` when no decider did
+    pub decider: Option<&'static str>,
+    /// The assessment of the program
+    pub assessment: Assessment,
+    /// The certificate that the decider found, if any
+    pub certificate: Option<Certificate>,
+}
+
+/// A sequence of deciders that are tried in order.
+pub struct Pipeline {
+    deciders: Vec<Box<dyn Decider>>,
+}
+
+impl Pipeline {
+    /// Create a pipeline without deciders.
+    pub fn new() -> Self {
+        Self {
+            deciders: Vec::new(),
+        }
+    }
+
+    /// Add a decider to the end of the pipeline.
+    pub fn with<D>(mut self, decider: D) -> Self
+    where
+        D: Decider + 'static,
+    {
+        self.deciders.push(Box::new(decider));
+        self
+    }
+
+    /// Run the deciders in order, until one of them settles the program.
+    ///
+    /// When no decider settles the program, it is assessed as `NotHalted`, with the details of the last decider that ran it.
+    pub fn run(&self, program: &CompleteProgram) -> Verdict {
+        let mut details = Details::unsimulated(program);
+        for decider in &self.deciders {
+            match decider.certify(program) {
+                Some((Assessment::NotHalted(run), _)) => details = run,
+                Some((assessment, certificate)) => {
+                    return Verdict {
+                        decider: Some(decider.name()),
+                        assessment,
+                        certificate,
+                    }
+                }
+                None => {}
+            }
+        }
+        Verdict {
+            decider: None,
+            assessment: Assessment::NotHalted(details),
+            certificate: None,
+        }
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decider::{CycleDetector, Reachability, Simulation};
+    use crate::turing::{Footprint, Program};
+
+    fn pipeline() -> Pipeline {
+        Pipeline::new()
+            .with(Reachability)
+            .with(Simulation::new(100))
+            .with(CycleDetector::new(100))
+    }
+
+    #[test]
+    fn the_first_decider_that_settles_a_program_is_recorded() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+
+        let verdict = pipeline().run(&program);
+
+        assert_eq!(Some("simulation"), verdict.decider);
+        assert!(matches!(verdict.assessment, Assessment::HaltedIn(_)));
+    }
+
+    #[test]
+    fn later_deciders_settle_what_earlier_deciders_can_not() {
+        let program: CompleteProgram = "1R1 0R1 0L0 1L0".parse().unwrap();
+
+        let verdict = pipeline().run(&program);
+
+        assert_eq!(Some("reachability"), verdict.decider);
+
+        let program: CompleteProgram = "1R1 0R1 0L0   H".parse().unwrap();
+
+        let verdict = pipeline().run(&program);
+
+        assert_eq!(Some("cycle"), verdict.decider);
+    }
+
+    #[test]
+    fn unsettled_programs_have_not_halted() {
+        let program: CompleteProgram = "1R0 1R0 1R0 1R0".parse().unwrap();
+
+        let verdict = Pipeline::new().with(Simulation::new(100)).run(&program);
+
+        assert_eq!(None, verdict.decider);
+        assert!(matches!(verdict.assessment, Assessment::NotHalted(_)));
+    }
+
+    #[test]
+    fn unsettled_programs_keep_the_details_of_the_last_run() {
+        let program: CompleteProgram = "1R0 1R1 1R0   H".parse().unwrap();
+
+        let verdict = Pipeline::new()
+            .with(Simulation::new(100))
+            .with(Reachability)
+            .with(Simulation::new(50))
+            .run(&program);
+
+        assert_eq!(None, verdict.decider);
+        assert_eq!(
+            Assessment::NotHalted(Details {
+                steps: 50,
+                score: 0,
+                multiplicity: program.multiplicity(),
+                footprint: Footprint {
+                    cells: 51,
+                    leftmost: 0,
+                    rightmost: 50,
+                },
+            }),
+            verdict.assessment
+        );
+    }
+}
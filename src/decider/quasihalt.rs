@@ -0,0 +1,186 @@
+//! Detect Turing machines that quasihalt, i.e. that never enter some of their states again after a certain step.
+//!
+//! In the *beeping busy beaver* game a Turing machine does not need to halt. It suffices that, after a certain step, it never
+//! enters one of its states again. A machine that halts quasihalts at the step it halts.
+//!
+//! Quasihalting is proven by finding a cycle, either in place or translated along the tape. Once in a cycle, the machine
+//! enters the same states in every period, forever. So the states that are not entered during a period are never entered
+//! again. The machine quasihalted after the last step it took in one of these omitted states, just like a halting machine
+//! halted after the last step it took.
+use crate::decider::{CycleDetector, Decider, TranslatedCycler};
+use crate::turing::{
+    Assessment, CompleteProgram, Details, Footprint, Key, Lookup, Machine, Program, SimpleTape,
+    State, Symbol, Tape,
+};
+
+/// Runs a Turing machine until it is found in a cycle, in order to detect that it quasihalts.
+pub struct QuasihaltDetector {
+    maximum: u128,
+}
+
+impl QuasihaltDetector {
+    /// Create a detector that runs a Turing machine for at most `maximum` steps.
+    pub fn new(maximum: u128) -> Self {
+        Self { maximum }
+    }
+
+    /// Run the Turing machine until it halts, is found to quasihalt or reached the maximum number of steps.
+    ///
+    /// A Turing machine whose cycle enters all of its states never quasihalts. Its cycle is returned instead.
+    pub fn run<T>(&self, machine: &mut Machine<T>) -> Assessment
+    where
+        T: Tape + Clone,
+    {
+        let start = machine.steps();
+        let mut translated = machine.clone();
+        let assessment = match CycleDetector::new(self.maximum).run(machine) {
+            Assessment::NotHalted(_) => {
+                let assessment = TranslatedCycler::new(self.maximum).run(&mut translated);
+                *machine = translated;
+                assessment
+            }
+            assessment => assessment,
+        };
+        let period = match assessment {
+            Assessment::Cycles { period, .. } | Assessment::TranslatedCycles { period, .. } => {
+                period
+            }
+            assessment => return assessment,
+        };
+        // The machine is in its cycle for at least the last period, so that period shows which states recur.
+        let recent = machine.steps() - period;
+        let omitted: Vec<State> = (0..states(machine.program()))
+            .map(State::Number)
+            .filter(|state| machine.last_visit(*state).is_none_or(|step| step <= recent))
+            .collect();
+        if omitted.is_empty() {
+            return assessment;
+        }
+        let steps = omitted
+            .iter()
+            .filter_map(|state| machine.last_visit(*state))
+            .max()
+            .map(|last| (last + 1).saturating_sub(start))
+            .unwrap_or(0);
+        Assessment::QuasiHalts {
+            omitted,
+            details: Details {
+                steps,
+                score: 0,
+                multiplicity: machine.program().multiplicity(),
+                footprint: Footprint::default(),
+            },
+        }
+    }
+}
+
+impl Decider for QuasihaltDetector {
+    fn name(&self) -> &'static str {
+        "quasihalt"
+    }
+
+    /// Settles the programs that quasihalt. Cycles that enter all of their states are left to the `CycleDetector` and the
+    /// `TranslatedCycler`, so a pipeline can try this decider before them and still count those cycles for them.
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        let mut machine = Machine::new(SimpleTape::empty(), program);
+        match self.run(&mut machine) {
+            Assessment::Cycles { .. } | Assessment::TranslatedCycles { .. } => None,
+            assessment => Some(assessment),
+        }
+    }
+}
+
+/// The number of states of a program, i.e. the number of states for which it knows an action.
+fn states(program: &dyn Program) -> u8 {
+    (0..u8::MAX)
+        .take_while(|state| {
+            let key = Key {
+                state: State::Number(*state),
+                symbol: Symbol::BLANK,
+            };
+            !matches!(program.lookup(&key), Lookup::Unknown)
+        })
+        .count() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decider::{Pipeline, Reachability, Simulation};
+    use crate::report::Report;
+    use crate::turing::{CompoundTape, Format};
+
+    fn assess(program: &str) -> Assessment {
+        let program = CompleteProgram::parse_as(program, Format::Standard).unwrap();
+        let mut machine = Machine::new(CompoundTape::empty(), &program);
+        QuasihaltDetector::new(1_000).run(&mut machine)
+    }
+
+    #[test]
+    fn the_beeping_busy_beaver_with_three_states_quasihalts() {
+        assert_eq!(
+            Assessment::QuasiHalts {
+                omitted: vec![State::Number(0), State::Number(1)],
+                details: Details {
+                    steps: 55,
+                    score: 0,
+                    multiplicity: 1,
+                    footprint: Footprint::default()
+                }
+            },
+            assess("1LB0RB_1RA0LC_1RC1RA")
+        );
+    }
+
+    #[test]
+    fn halting_machines_halt() {
+        assert!(matches!(
+            assess("1RB1LB_1LA1RZ"),
+            Assessment::HaltedIn(Details { steps: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn machines_that_enter_all_states_forever_do_not_quasihalt() {
+        assert!(matches!(
+            assess("1RB0RB_1RA0RA"),
+            Assessment::TranslatedCycles { .. }
+        ));
+        assert!(matches!(assess("0RB1RZ_0LA0LA"), Assessment::Cycles { .. }));
+    }
+
+    #[test]
+    fn pipelines_settle_quasihalting_machines_before_their_cycles() {
+        // Machines without a reachable halting transition quasihalt as well, so `Reachability` comes after quasihalting.
+        let pipeline = Pipeline::new()
+            .with(Simulation::new(1_000))
+            .with(QuasihaltDetector::new(1_000))
+            .with(Reachability)
+            .with(CycleDetector::new(1_000))
+            .with(TranslatedCycler::new(1_000));
+        let decider = |program: &str| {
+            let program = CompleteProgram::parse_as(program, Format::Standard).unwrap();
+            pipeline.run(&program).decider
+        };
+
+        assert_eq!(Some("quasihalt"), decider("1LB0RB_1RA0LC_1RC1RA"));
+        assert_eq!(Some("simulation"), decider("1RB1LB_1LA1RZ"));
+        assert_eq!(Some("cycle"), decider("0RB1RZ_0LA0LA"));
+        assert_eq!(Some("translated cycle"), decider("1RB0RB_1RA1RZ"));
+        assert_eq!(Some("reachability"), decider("1RB0RB_1RA0RA"));
+
+        let mut report = Report::new();
+        for program in ["1LB0RB_1RA0LC_1RC1RA", "1RB1LB_1LA1RZ", "1RB0RB_1RA0RA"] {
+            let parsed = CompleteProgram::parse_as(program, Format::Standard).unwrap();
+            report.update_with_program_verdict(&program, &pipeline.run(&parsed));
+        }
+        let champions = report.champions();
+        let (_, bbb) = champions
+            .iter()
+            .find(|(name, _)| *name == "bbb")
+            .expect("a beeping busy beaver champion");
+
+        assert_eq!(55, bbb.details().steps);
+        assert_eq!(vec!["1LB0RB_1RA0LC_1RC1RA".to_owned()], bbb.programs());
+    }
+}
@@ -0,0 +1,59 @@
+//! Decide that a Turing machine never halts, because its program graph can not reach a halting transition.
+//!
+//! This decider does not run the Turing machine, so it is cheap to try before any simulation.
+use crate::decider::Decider;
+use crate::graph::Analysis;
+use crate::turing::{Assessment, CompleteProgram, Details};
+
+/// Classifies programs whose halting transitions can not be reached from state `0`.
+pub struct Reachability;
+
+impl Reachability {
+    /// Assess a program without running it. Returns `None` when a halting transition can be reached.
+    pub fn decide(&self, program: &CompleteProgram) -> Option<Assessment> {
+        if Analysis::of(program).halt_reachable() {
+            None
+        } else {
+            Some(Assessment::HaltUnreachable(Details::unsimulated(program)))
+        }
+    }
+}
+
+impl Decider for Reachability {
+    fn name(&self) -> &'static str {
+        "reachability"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        self.decide(program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn programs_with_unreachable_halting_transitions_never_halt() {
+        let program: CompleteProgram = "1R0 1L0 1R2   H   H 0L0".parse().unwrap();
+
+        assert!(matches!(
+            Reachability.decide(&program),
+            Some(Assessment::HaltUnreachable(_))
+        ));
+    }
+
+    #[test]
+    fn programs_with_reachable_halting_transitions_are_undecided() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+
+        assert_eq!(None, Reachability.decide(&program));
+    }
+
+    #[test]
+    fn programs_that_write_before_halting_are_undecided() {
+        let program: CompleteProgram = "1R1 1L1 1L0 1LH".parse().unwrap();
+
+        assert_eq!(None, Reachability.decide(&program));
+    }
+}
@@ -0,0 +1,205 @@
+//! Decide that a Turing machine never halts by reasoning backwards within a segment of the tape.
+//!
+//! Only a segment of `width` cells is tracked. A configuration within the segment consists of the state, the position of the
+//! head and the cells of the segment, some of which can be unknown. The head can also be outside the segment, to the left or
+//! to the right, where nothing is known about the tape.
+//!
+//! Starting from the halting transitions, with the head at every position, all configurations that could have lead to them are
+//! explored exhaustively. Because a segment has only finitely many configurations, the exploration ends. When, for some
+//! position of the head, no configuration in state `0` on a blank segment is found, the machine started with its head at that
+//! position never halts.
+use crate::decider::Decider;
+use crate::turing::{Action, Assessment, CompleteProgram, Details, Direction, State, Symbol};
+use std::collections::HashSet;
+
+/// Reasons backwards from the halting transitions of a program within segments of increasing width.
+pub struct HaltingSegment {
+    maximum: usize,
+}
+
+impl HaltingSegment {
+    /// Create a decider that tries segments with a width up to `maximum`.
+    pub fn new(maximum: usize) -> Self {
+        Self { maximum }
+    }
+
+    /// Reason backwards within segments of a program. Returns `None` when no segment suffices.
+    pub fn decide(&self, program: &CompleteProgram) -> Option<Assessment> {
+        (1..=self.maximum)
+            .find(|width| refutes(program, *width))
+            .map(|width| Assessment::SegmentRefuted {
+                width,
+                details: Details::unsimulated(program),
+            })
+    }
+}
+
+impl Decider for HaltingSegment {
+    fn name(&self) -> &'static str {
+        "halting segment"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        self.decide(program)
+    }
+}
+
+/// Whether a segment of `width` cells shows that the program never halts.
+fn refutes(program: &CompleteProgram, width: usize) -> bool {
+    let mut starts = vec![false; width];
+    let mut visited = HashSet::new();
+    let mut configurations: Vec<Configuration> = program
+        .into_iter()
+        .filter(|(_, action)| action.halts())
+        .flat_map(|(key, _)| {
+            (-1..=width as isize).map(move |head| {
+                let mut cells = vec![None; width];
+                if let Some(cell) = Configuration::inside(width, head) {
+                    cells[cell] = Some(key.symbol);
+                }
+                Configuration {
+                    state: key.state,
+                    head,
+                    cells,
+                }
+            })
+        })
+        .collect();
+    while let Some(configuration) = configurations.pop() {
+        if !visited.insert(configuration.clone()) {
+            continue;
+        }
+        if let Some(start) = configuration.could_start() {
+            starts[start] = true;
+            if starts.iter().all(|start| *start) {
+                return false;
+            }
+        }
+        configurations.extend(configuration.predecessors(program));
+    }
+    true
+}
+
+/// A configuration of a Turing machine within a segment of the tape.
+///
+/// A head at `-1` or at the width of the segment is somewhere to the left or to the right of the segment.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+struct Configuration {
+    state: State,
+    head: isize,
+    cells: Vec<Option<Symbol>>,
+}
+
+impl Configuration {
+    fn inside(width: usize, head: isize) -> Option<usize> {
+        if 0 <= head && head < width as isize {
+            Some(head as usize)
+        } else {
+            None
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The position of the head when this configuration could be the start of a run.
+    fn could_start(&self) -> Option<usize> {
+        Configuration::inside(self.width(), self.head).filter(|_| {
+            self.state == State::Number(0)
+                && self
+                    .cells
+                    .iter()
+                    .all(|cell| cell.is_none_or(|symbol| symbol == Symbol::BLANK))
+        })
+    }
+
+    fn predecessors(&self, program: &CompleteProgram) -> Vec<Configuration> {
+        let outside = Configuration::inside(self.width(), self.head).is_none();
+        program
+            .into_iter()
+            .flat_map(|(key, action)| match action {
+                Action::Do {
+                    symbol,
+                    direction,
+                    state,
+                } if state == self.state => {
+                    let head = match direction {
+                        Direction::Left => self.head + 1,
+                        Direction::Right => self.head - 1,
+                    };
+                    let mut predecessors = Vec::new();
+                    if outside {
+                        // Outside the segment the head can wander in any direction.
+                        predecessors.push(Configuration {
+                            state: key.state,
+                            head: self.head,
+                            cells: self.cells.clone(),
+                        });
+                    }
+                    match Configuration::inside(self.width(), head) {
+                        Some(cell) if self.cells[cell].is_none_or(|known| known == symbol) => {
+                            let mut cells = self.cells.clone();
+                            cells[cell] = Some(key.symbol);
+                            predecessors.push(Configuration {
+                                state: key.state,
+                                head,
+                                cells,
+                            });
+                        }
+                        Some(_) => {}
+                        None if !outside => predecessors.push(Configuration {
+                            state: key.state,
+                            head,
+                            cells: self.cells.clone(),
+                        }),
+                        None => {}
+                    }
+                    predecessors
+                }
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{Machine, Programs, SimpleTape};
+
+    fn width(program: &str) -> Option<usize> {
+        let program: CompleteProgram = program.parse().unwrap();
+        match HaltingSegment::new(6).decide(&program) {
+            Some(Assessment::SegmentRefuted { width, .. }) => Some(width),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn programs_without_halting_transitions_do_not_halt() {
+        assert_eq!(Some(1), width("1R0 1L0 1R0 1L0"));
+    }
+
+    #[test]
+    fn programs_that_never_write_the_symbol_they_halt_on_do_not_halt() {
+        assert_eq!(Some(2), width("0L1   H 0R0 0L0"));
+    }
+
+    #[test]
+    fn halting_programs_are_not_refuted() {
+        assert_eq!(None, width("1R1 1L1 1L0   H"));
+        assert_eq!(None, width("1R1 1L1 1L0 1LH"));
+    }
+
+    #[test]
+    fn decisions_agree_with_running_machines() {
+        let decider = HaltingSegment::new(4);
+        for program in Programs::all(2) {
+            if decider.decide(&program).is_some() {
+                let mut machine = Machine::new(SimpleTape::empty(), &program);
+                assert!(!matches!(machine.run(100), Assessment::HaltedIn(_)));
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Settle programs that halt by running them.
+use crate::decider::Decider;
+use crate::turing::{Assessment, CompleteProgram, Machine, SimpleTape};
+
+/// Runs a Turing machine for a maximum number of steps.
+pub struct Simulation {
+    maximum: u128,
+}
+
+impl Simulation {
+    /// Create a decider that runs Turing machines for at most `maximum` steps.
+    pub fn new(maximum: u128) -> Self {
+        Self { maximum }
+    }
+}
+
+impl Decider for Simulation {
+    fn name(&self) -> &'static str {
+        "simulation"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        let mut machine = Machine::new(SimpleTape::empty(), program);
+        Some(machine.run(self.maximum))
+    }
+}
@@ -0,0 +1,216 @@
+//! Detect Turing machines that repeat the same local pattern while drifting into blank tape.
+//!
+//! A *record* is a step at which the tape head visits a cell further to the right, or further to the left, than ever before.
+//! Take two records in the same direction, in the same state, at positions `p` and `p + d`. Let `m` be the position closest to
+//! the far end of the tape that the head visited in between. The machine only read the cells between `m` and `p + d`, all
+//! cells beyond the head are blank at a record, so when the cells between `m` and `p` at the first record are equal to the
+//! cells between `m + d` and `p + d` at the second record, the machine will repeat the same steps translated by `d`. It
+//! does so forever, drifting into blank tape.
+use crate::decider::Decider;
+use crate::turing::{
+    Assessment, CompleteProgram, Details, Machine, Progress, SimpleTape, State, Symbol, Tape,
+};
+
+/// Runs a Turing machine while remembering its records, in order to detect a translated cycle.
+pub struct TranslatedCycler {
+    maximum: u128,
+}
+
+impl TranslatedCycler {
+    /// Create a detector that runs a Turing machine for at most `maximum` steps.
+    pub fn new(maximum: u128) -> Self {
+        Self { maximum }
+    }
+
+    /// Run the Turing machine until it halts, is found to be a translated cycler or reached the maximum number of steps.
+    pub fn run<T>(&self, machine: &mut Machine<T>) -> Assessment
+    where
+        T: Tape,
+    {
+        let mut right = Records::new(1);
+        let mut left = Records::new(-1);
+        right.record(0, machine);
+        left.record(0, machine);
+        let mut steps: u128 = 0;
+        while !machine.state().halted() && steps < self.maximum {
+            match machine.step() {
+                Progress::Made => steps += 1,
+                progress => {
+                    return Assessment::NoProgress(
+                        progress,
+                        Details::from_machine(machine, steps, 0),
+                    )
+                }
+            }
+            for records in [&mut right, &mut left] {
+                if let Some((preperiod, distance)) = records.update(steps, machine) {
+                    return Assessment::TranslatedCycles {
+                        preperiod,
+                        period: steps - preperiod,
+                        distance,
+                        details: Details::from_machine(machine, steps, 0),
+                    };
+                }
+            }
+        }
+        if machine.state().halted() {
+            Assessment::HaltedIn(Details::from_machine(machine, steps, machine.score()))
+        } else {
+            Assessment::NotHalted(Details::from_machine(machine, steps, 0))
+        }
+    }
+}
+
+impl Decider for TranslatedCycler {
+    fn name(&self) -> &'static str {
+        "translated cycle"
+    }
+
+    fn settle(&self, program: &CompleteProgram) -> Option<Assessment> {
+        let mut machine = Machine::new(SimpleTape::empty(), program);
+        Some(self.run(&mut machine))
+    }
+}
+
+/// The records in a single direction. Positions are multiplied by `sign`, so that records are always to the right.
+struct Records {
+    sign: i128,
+    records: Vec<Record>,
+}
+
+/// The configuration of a Turing machine at a record.
+struct Record {
+    steps: u128,
+    state: State,
+    position: i128,
+    /// The lowest position visited since this record
+    lowest: i128,
+    /// The symbols behind the head, nearest first
+    behind: Vec<Symbol>,
+}
+
+impl Records {
+    fn new(sign: i128) -> Self {
+        Self {
+            sign,
+            records: Vec::new(),
+        }
+    }
+
+    /// Update the records with the current configuration. Returns the steps and the signed distance of an earlier record that
+    /// the current record repeats.
+    fn update<T>(&mut self, steps: u128, machine: &Machine<T>) -> Option<(u128, i128)>
+    where
+        T: Tape,
+    {
+        let position = self.sign * machine.tape().position();
+        for record in self.records.iter_mut().rev() {
+            if record.lowest <= position {
+                break;
+            }
+            record.lowest = position;
+        }
+        let highest = self.records.last().map(|record| record.position);
+        if highest.is_none_or(|highest| position > highest) {
+            self.record(steps, machine);
+            let sign = self.sign;
+            let (current, earlier) = self.records.split_last()?;
+            return earlier
+                .iter()
+                .find(|record| record.repeated_by(current))
+                .map(|record| (record.steps, sign * (current.position - record.position)));
+        }
+        None
+    }
+
+    fn record<T>(&mut self, steps: u128, machine: &Machine<T>)
+    where
+        T: Tape,
+    {
+        let snapshot = machine.tape().snapshot();
+        let behind = if self.sign > 0 {
+            snapshot.left
+        } else {
+            snapshot.right.into_iter().skip(1).collect()
+        };
+        let position = self.sign * machine.tape().position();
+        self.records.push(Record {
+            steps,
+            state: machine.state(),
+            position,
+            lowest: position,
+            behind,
+        });
+    }
+}
+
+impl Record {
+    fn repeated_by(&self, later: &Record) -> bool {
+        let width = (self.position - self.lowest) as usize;
+        self.state == later.state && (0..width).all(|i| self.symbol(i) == later.symbol(i))
+    }
+
+    fn symbol(&self, index: usize) -> Symbol {
+        *self.behind.get(index).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{CompleteProgram, CompoundTape, SimpleTape};
+
+    fn assess(program: &str) -> [Assessment; 2] {
+        let program: CompleteProgram = program.parse().unwrap();
+        let cycler = TranslatedCycler::new(1_000);
+        [
+            cycler.run(&mut Machine::new(SimpleTape::empty(), &program)),
+            cycler.run(&mut Machine::new(CompoundTape::empty(), &program)),
+        ]
+    }
+
+    #[test]
+    fn drifting_programs_are_translated_cyclers() {
+        for assessment in assess("1R0 1R0 1R0 1R0") {
+            assert!(matches!(
+                assessment,
+                Assessment::TranslatedCycles {
+                    period: 1,
+                    distance: 1,
+                    ..
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn programs_can_drift_to_the_left() {
+        for assessment in assess("1L1 0L0 1R0 0L0") {
+            assert!(matches!(
+                assessment,
+                Assessment::TranslatedCycles { distance, .. } if distance < 0
+            ));
+        }
+    }
+
+    #[test]
+    fn translated_cyclers_can_look_back() {
+        for assessment in assess("1L1 1R0 1R0 0R1") {
+            assert!(matches!(
+                assessment,
+                Assessment::TranslatedCycles {
+                    period: 4,
+                    distance: 2,
+                    ..
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn halting_programs_halt() {
+        for assessment in assess("1R1 1L1 1L0   H") {
+            assert!(matches!(assessment, Assessment::HaltedIn(_)));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Analyze the directed graph of a complete program.
+//!
+//! The graph of a program over-approximates the behaviour of its Turing machine: every state that the machine enters can be
+//! reached in the graph from state `0`. So when no halting transition leaves a reachable state, the machine never halts.
+use crate::turing::{Action, CompleteProgram, Key, State};
+use std::collections::HashSet;
+
+/// The reachability of the states and halting transitions of a program.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Analysis {
+    reachable: HashSet<State>,
+    halting: Vec<Key>,
+}
+
+impl Analysis {
+    /// Analyze the graph of a complete program.
+    pub fn of(program: &CompleteProgram) -> Self {
+        let mut reachable = HashSet::new();
+        let mut frontier = vec![State::Number(0)];
+        while let Some(current) = frontier.pop() {
+            if reachable.insert(current) {
+                frontier.extend(
+                    program
+                        .into_iter()
+                        .filter_map(|(key, action)| match action {
+                            Action::Do {
+                                state: state @ State::Number(_),
+                                ..
+                            } if key.state == current => Some(state),
+                            _ => None,
+                        }),
+                );
+            }
+        }
+        let halting = program
+            .into_iter()
+            .filter(|(key, action)| action.halts() && reachable.contains(&key.state))
+            .map(|(key, _)| key)
+            .collect();
+        Self { reachable, halting }
+    }
+
+    /// Determine if a state can be reached from state `0`.
+    pub fn is_reachable(&self, state: &State) -> bool {
+        self.reachable.contains(state)
+    }
+
+    /// The states that can be reached from state `0`, in increasing order.
+    pub fn reachable_states(&self) -> Vec<State> {
+        let mut states: Vec<State> = self.reachable.iter().copied().collect();
+        states.sort_by_key(|state| match state {
+            State::Number(n) => *n,
+            State::Halted => u8::MAX,
+        });
+        states
+    }
+
+    /// The halting transitions that leave a reachable state.
+    pub fn halting_transitions(&self) -> &[Key] {
+        &self.halting
+    }
+
+    /// Determine if any halting transition can be reached from state `0`.
+    pub fn halt_reachable(&self) -> bool {
+        !self.halting.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::Symbol;
+
+    #[test]
+    fn unreachable_states_are_detected() {
+        let program: CompleteProgram = "1R0 1L0 1R2   H   H 0L0".parse().unwrap();
+
+        let analysis = Analysis::of(&program);
+
+        assert_eq!(vec![State::Number(0)], analysis.reachable_states());
+        assert!(!analysis.is_reachable(&State::Number(1)));
+        assert!(!analysis.halt_reachable());
+    }
+
+    #[test]
+    fn reachable_halting_transitions_are_detected() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H 1R2   H".parse().unwrap();
+
+        let analysis = Analysis::of(&program);
+
+        assert_eq!(
+            vec![State::Number(0), State::Number(1)],
+            analysis.reachable_states()
+        );
+        let halting: Key = (State::Number(1), Symbol::NON_BLANK).into();
+        assert_eq!(&[halting], analysis.halting_transitions());
+        assert!(analysis.halt_reachable());
+    }
+
+    #[test]
+    fn actions_that_write_before_halting_are_halting_transitions() {
+        let program: CompleteProgram = "1R1 1L1 1L0 1LH".parse().unwrap();
+
+        let analysis = Analysis::of(&program);
+
+        assert_eq!(
+            vec![State::Number(0), State::Number(1)],
+            analysis.reachable_states()
+        );
+        let halting: Key = (State::Number(1), Symbol::NON_BLANK).into();
+        assert_eq!(&[halting], analysis.halting_transitions());
+    }
+}
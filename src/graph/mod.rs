@@ -20,6 +20,11 @@
 //!
 //! ![A directed graph representation of Rabo champion](https://fifth-postulate.nl/busy-beaver/image/rado.svg)
 //!
+//! Besides drawing it, the graph can be analyzed with `Analysis`, e.g. to find out whether a halting transition is reachable.
+mod analysis;
+
+pub use analysis::Analysis;
+
 use crate::turing::{Action, CompleteProgram};
 use dot_writer::{Attributes, DotWriter};
 use std::fmt::{Result, Write as FmtWrite};
@@ -5,9 +5,12 @@
 //! with a certain number of states. Tibor Rado introduced the concept in 1962 and showed that the busy beaver function is uncomputable, i.e.
 //! there is no Turing machine that computes the maximum running time of an n-state Turing machine.
 
+pub mod database;
+pub mod decider;
 pub mod graph;
 pub mod report;
 pub mod turing;
+pub mod verifier;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,494 @@
+//! Write reports as [JSON](https://www.json.org/) and read them back.
+//!
+//! The keys of a report and of its champions are fixed, so downstream tools can rely on them. A report looks like
+//!
+//! ```text
+//! {"sampled":false,"subjects":2,"total":2,"halted":1,"indeterminate":0,"stuck":0,"non_halting":1,
+//!  "decided":{"cycle":1},"halted_steps":6,"halted_squared_steps":36,
+//!  "champions":{"sigma":{...},"s":{...},"space":{...},"bbb":{...}}}
+//! ```
+//!
+//! where a missing champion is `null` and a champion looks like
+//!
+//! ```text
+//! {"steps":6,"score":4,"multiplicity":1,"cells":4,"leftmost":-2,"rightmost":1,"count":1,"programs":["1RB1LB_1LA1RZ"]}
+//! ```
+//!
+//! Reading a report back only understands JSON of this shape. The number of ties is not part of the JSON, so a report that is
+//! read back retains the default number of ties.
+use super::{Champion, Report, TIES};
+use crate::turing::{Details, Footprint};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+impl Report {
+    /// The report as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        let decided: Vec<String> = self
+            .decided
+            .iter()
+            .map(|(decider, count)| format!("{}:{}", string(decider), count))
+            .collect();
+        let champion = |champion: &Option<Champion>| {
+            champion
+                .as_ref()
+                .map(Champion::to_json)
+                .unwrap_or_else(|| "null".to_string())
+        };
+        format!(
+            "{{\"sampled\":{},\"subjects\":{},\"total\":{},\"halted\":{},\"indeterminate\":{},\"stuck\":{},\"non_halting\":{},\"decided\":{{{}}},\"halted_steps\":{},\"halted_squared_steps\":{},\"champions\":{{\"sigma\":{},\"s\":{},\"space\":{},\"bbb\":{}}}}}",
+            self.sampled,
+            self.subjects,
+            self.total,
+            self.halted,
+            self.indeterminate,
+            self.stuck,
+            self.non_halting,
+            decided.join(","),
+            self.halted_steps,
+            self.halted_squared_steps,
+            champion(&self.sigma_champion),
+            champion(&self.s_champion),
+            champion(&self.space_champion),
+            champion(&self.bbb_champion),
+        )
+    }
+}
+
+impl Report {
+    /// Read a report from the JSON that `Report::to_json` writes.
+    pub fn from_json(input: &str) -> Result<Self, JsonError> {
+        let value = Parser::new(input).document()?;
+        let decided = value
+            .get("decided")?
+            .object()?
+            .iter()
+            .map(|(decider, count)| Ok((decider.clone(), count.number()?)))
+            .collect::<Result<BTreeMap<String, usize>, JsonError>>()?;
+        let champions = value.get("champions")?;
+        let champion = |key: &'static str| match champions.get(key)? {
+            Value::Null => Ok(None),
+            champion => Champion::from_value(champion).map(Some),
+        };
+        Ok(Self {
+            sampled: value.get("sampled")?.boolean()?,
+            subjects: value.get("subjects")?.number()?,
+            total: value.get("total")?.number()?,
+            halted: value.get("halted")?.number()?,
+            indeterminate: value.get("indeterminate")?.number()?,
+            stuck: value.get("stuck")?.number()?,
+            non_halting: value.get("non_halting")?.number()?,
+            decided,
+            halted_steps: value.get("halted_steps")?.number()?,
+            halted_squared_steps: value.get("halted_squared_steps")?.number()?,
+            sigma_champion: champion("sigma")?,
+            s_champion: champion("s")?,
+            space_champion: champion("space")?,
+            bbb_champion: champion("bbb")?,
+            ties: TIES,
+        })
+    }
+}
+
+impl Champion {
+    fn from_value(value: &Value) -> Result<Self, JsonError> {
+        Ok(Self {
+            details: Details {
+                steps: value.get("steps")?.number()?,
+                score: value.get("score")?.number()?,
+                multiplicity: value.get("multiplicity")?.number()?,
+                footprint: Footprint {
+                    cells: value.get("cells")?.number()?,
+                    leftmost: value.get("leftmost")?.number()?,
+                    rightmost: value.get("rightmost")?.number()?,
+                },
+            },
+            count: value.get("count")?.number()?,
+            programs: value
+                .get("programs")?
+                .array()?
+                .iter()
+                .map(|program| program.string().map(str::to_string))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The champion as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        let programs: Vec<String> = self
+            .programs
+            .iter()
+            .map(|program| string(program))
+            .collect();
+        format!(
+            "{{\"steps\":{},\"score\":{},\"multiplicity\":{},\"cells\":{},\"leftmost\":{},\"rightmost\":{},\"count\":{},\"programs\":[{}]}}",
+            self.details.steps,
+            self.details.score,
+            self.details.multiplicity,
+            self.details.footprint.cells,
+            self.details.footprint.leftmost,
+            self.details.footprint.rightmost,
+            self.count,
+            programs.join(","),
+        )
+    }
+}
+
+/// A JSON string, with quotes, backslashes and control characters escaped.
+fn string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            character if character.is_control() => {
+                result.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => result.push(character),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// The problems that can occur when reading a report from JSON.
+#[derive(Debug, PartialEq, Eq)]
+pub enum JsonError {
+    /// The input is not JSON, the problem starts at this byte offset
+    Malformed(usize),
+    /// A key of the report is missing
+    Missing(&'static str),
+    /// A value has an unexpected type or is out of range
+    Unexpected,
+}
+
+/// The values of a JSON document. Numbers retain their text, so they can be read as any type of number.
+#[derive(Debug, PartialEq)]
+enum Value {
+    Null,
+    Boolean(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn get(&self, key: &'static str) -> Result<&Value, JsonError> {
+        self.object()?
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+            .ok_or(JsonError::Missing(key))
+    }
+
+    fn object(&self) -> Result<&[(String, Value)], JsonError> {
+        match self {
+            Value::Object(members) => Ok(members),
+            _ => Err(JsonError::Unexpected),
+        }
+    }
+
+    fn array(&self) -> Result<&[Value], JsonError> {
+        match self {
+            Value::Array(elements) => Ok(elements),
+            _ => Err(JsonError::Unexpected),
+        }
+    }
+
+    fn string(&self) -> Result<&str, JsonError> {
+        match self {
+            Value::String(text) => Ok(text),
+            _ => Err(JsonError::Unexpected),
+        }
+    }
+
+    fn boolean(&self) -> Result<bool, JsonError> {
+        match self {
+            Value::Boolean(value) => Ok(*value),
+            _ => Err(JsonError::Unexpected),
+        }
+    }
+
+    fn number<N: FromStr>(&self) -> Result<N, JsonError> {
+        match self {
+            Value::Number(text) => text.parse().map_err(|_| JsonError::Unexpected),
+            _ => Err(JsonError::Unexpected),
+        }
+    }
+}
+
+/// A recursive descent parser for JSON.
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// A single value, surrounded by nothing but whitespace.
+    fn document(&mut self) -> Result<Value, JsonError> {
+        let value = self.value()?;
+        self.whitespace();
+        if self.position < self.input.len() {
+            return Err(self.malformed());
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.whitespace();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Value::String),
+            Some('-' | '0'..='9') => Ok(self.number()),
+            _ if self.keyword("null") => Ok(Value::Null),
+            _ if self.keyword("true") => Ok(Value::Boolean(true)),
+            _ if self.keyword("false") => Ok(Value::Boolean(false)),
+            _ => Err(self.malformed()),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, JsonError> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        self.whitespace();
+        if self.eat('}') {
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(':')?;
+            members.push((key, self.value()?));
+            self.whitespace();
+            if self.eat('}') {
+                return Ok(Value::Object(members));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, JsonError> {
+        self.expect('[')?;
+        let mut elements = Vec::new();
+        self.whitespace();
+        if self.eat(']') {
+            return Ok(Value::Array(elements));
+        }
+        loop {
+            elements.push(self.value()?);
+            self.whitespace();
+            if self.eat(']') {
+                return Ok(Value::Array(elements));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut result = String::new();
+        loop {
+            let character = self.next().ok_or_else(|| self.malformed())?;
+            match character {
+                '"' => return Ok(result),
+                '\\' => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode()?,
+                        _ => return Err(self.malformed()),
+                    };
+                    result.push(escaped);
+                }
+                character if character.is_control() => return Err(self.malformed()),
+                character => result.push(character),
+            }
+        }
+    }
+
+    /// The character of a `\u` escape, without support for surrogate pairs.
+    fn unicode(&mut self) -> Result<char, JsonError> {
+        let digits = self
+            .input
+            .get(self.position..self.position + 4)
+            .ok_or_else(|| self.malformed())?;
+        let character = u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.malformed())?;
+        self.position += 4;
+        Ok(character)
+    }
+
+    fn number(&mut self) -> Value {
+        let start = self.position;
+        while let Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9') = self.peek() {
+            self.position += 1;
+        }
+        Value::Number(self.input[start..self.position].to_string())
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.input[self.position..].starts_with(keyword);
+        if found {
+            self.position += keyword.len();
+        }
+        found
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.malformed())
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.position += expected.len_utf8();
+        }
+        found
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+
+    fn malformed(&self) -> JsonError {
+        JsonError::Malformed(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decider::Verdict;
+    use crate::turing::{Assessment, Details, Footprint};
+
+    fn details(steps: u128) -> Details {
+        Details {
+            steps,
+            score: 4,
+            multiplicity: 1,
+            footprint: Footprint {
+                cells: 4,
+                leftmost: -2,
+                rightmost: 1,
+            },
+        }
+    }
+
+    #[test]
+    fn reports_can_be_written_as_json() {
+        let mut report = Report::new();
+        report.update_with_program(&"1RB1LB_1LA1RZ", &Assessment::HaltedIn(details(6)));
+        report.update_with_verdict(&Verdict {
+            decider: Some("cycle"),
+            assessment: Assessment::Cycles {
+                preperiod: 0,
+                period: 2,
+                details: details(2),
+            },
+            certificate: None,
+        });
+
+        assert_eq!(
+            concat!(
+                r#"{"sampled":false,"subjects":2,"total":2,"halted":1,"indeterminate":0,"stuck":0,"non_halting":1,"#,
+                r#""decided":{"cycle":1},"halted_steps":6,"halted_squared_steps":36,"champions":{"#,
+                r#""sigma":{"steps":6,"score":4,"multiplicity":1,"cells":4,"leftmost":-2,"rightmost":1,"count":1,"programs":["1RB1LB_1LA1RZ"]},"#,
+                r#""s":{"steps":6,"score":4,"multiplicity":1,"cells":4,"leftmost":-2,"rightmost":1,"count":1,"programs":["1RB1LB_1LA1RZ"]},"#,
+                r#""space":{"steps":6,"score":4,"multiplicity":1,"cells":4,"leftmost":-2,"rightmost":1,"count":1,"programs":["1RB1LB_1LA1RZ"]},"#,
+                r#""bbb":{"steps":6,"score":4,"multiplicity":1,"cells":4,"leftmost":-2,"rightmost":1,"count":1,"programs":["1RB1LB_1LA1RZ"]}}}"#
+            ),
+            report.to_json()
+        );
+    }
+
+    #[test]
+    fn missing_champions_are_null() {
+        let json = Report::new().to_json();
+
+        assert!(json.ends_with(r#""champions":{"sigma":null,"s":null,"space":null,"bbb":null}}"#));
+    }
+
+    #[test]
+    fn reports_can_be_read_back_from_json() {
+        let mut report = Report::new();
+        report.update_with_program(&"1RB1LB_1LA1RZ", &Assessment::HaltedIn(details(6)));
+        report.update_with_program(&"say \"H\"\\\n", &Assessment::HaltedIn(details(6)));
+        report.update_with_verdict(&Verdict {
+            decider: Some("cycle"),
+            assessment: Assessment::Cycles {
+                preperiod: 0,
+                period: 2,
+                details: details(2),
+            },
+            certificate: None,
+        });
+
+        let json = report.to_json();
+
+        assert_eq!(Ok(report), Report::from_json(&json));
+        assert_eq!(
+            Ok(Report::new()),
+            Report::from_json(&Report::new().to_json())
+        );
+    }
+
+    #[test]
+    fn reading_json_checks_for_errors() {
+        assert_eq!(Err(JsonError::Malformed(0)), Report::from_json(""));
+        assert_eq!(
+            Err(JsonError::Malformed(11)),
+            Report::from_json(r#"{"sampled" false}"#)
+        );
+        let json = Report::new().to_json();
+        assert_eq!(
+            Err(JsonError::Missing("sampled")),
+            Report::from_json(&json.replace(r#""sampled":false,"#, ""))
+        );
+        assert_eq!(
+            Err(JsonError::Unexpected),
+            Report::from_json(&json.replace(r#""sampled":false"#, r#""sampled":1"#))
+        );
+        assert_eq!(
+            Err(JsonError::Unexpected),
+            Report::from_json(&json.replace(r#""stuck":0"#, r#""stuck":-1"#))
+        );
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        assert_eq!(r#""1R1 \"H\"\\\n""#, string("1R1 \"H\"\\\n"));
+    }
+}
@@ -0,0 +1,276 @@
+//! Log the assessment of every Turing machine as a line of comma separated values.
+//!
+//! The log starts with the header `program,verdict,decider,steps,score,multiplicity`. Every following line describes a Turing
+//! machine: its program, the kind of assessment, e.g. `halted` or `cycles`, the decider that settled it, which is empty when no
+//! decider did, and the details of its run. Programs are written as given, so they should not contain commas. Both the native
+//! and the standard format of programs qualify.
+//!
+//! The following code writes a log and reads it back
+//!
+//! ```
+//! # use busy_beaver::{report::{LogReader, LogWriter}, turing::{Machine, Programs, SimpleTape}};
+//! let mut output = Vec::new();
+//! let mut writer = LogWriter::new(&mut output).expect("a log");
+//! for program in Programs::all(1) {
+//!     let mut machine = Machine::new(SimpleTape::empty(), &program);
+//!     writer.write(&program, &machine.run(100)).expect("an entry");
+//! }
+//! let reader = LogReader::new(output.as_slice()).expect("a log");
+//! for entry in reader {
+//!     println!("{:?}", entry.expect("an entry"));
+//! }
+//! ```
+use crate::decider::Verdict;
+use crate::turing::Assessment;
+use std::fmt::Display;
+use std::io::{self, BufRead, Lines, Write};
+
+/// The first line of a log.
+pub const HEADER: &str = "program,verdict,decider,steps,score,multiplicity";
+
+/// The assessment of a single Turing machine, as read from a log.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Entry {
+    /// The program of the Turing machine
+    pub program: String,
+    /// The kind of assessment, e.g. `halted`
+    pub verdict: String,
+    /// The decider that settled the program, if any
+    pub decider: Option<String>,
+    /// Number of steps taken
+    pub steps: u128,
+    /// Number of non blank symbols on the tape
+    pub score: usize,
+    /// The multiplicity of the program
+    pub multiplicity: usize,
+}
+
+/// Writes the assessments of Turing machines to a log.
+pub struct LogWriter<W> {
+    output: W,
+}
+
+impl<W> LogWriter<W>
+where
+    W: Write,
+{
+    /// Create a writer for a log. The header is written immediately.
+    pub fn new(mut output: W) -> Result<Self, LogError> {
+        writeln!(output, "{}", HEADER)?;
+        Ok(Self { output })
+    }
+
+    /// Write the assessment of a program to the log.
+    pub fn write(
+        &mut self,
+        program: &dyn Display,
+        assessment: &Assessment,
+    ) -> Result<(), LogError> {
+        self.entry(program, assessment, None)
+    }
+
+    /// Write a verdict about a program to the log.
+    pub fn write_verdict(
+        &mut self,
+        program: &dyn Display,
+        verdict: &Verdict,
+    ) -> Result<(), LogError> {
+        self.entry(program, &verdict.assessment, verdict.decider)
+    }
+
+    fn entry(
+        &mut self,
+        program: &dyn Display,
+        assessment: &Assessment,
+        decider: Option<&str>,
+    ) -> Result<(), LogError> {
+        let details = assessment.details();
+        writeln!(
+            self.output,
+            "{},{},{},{},{},{}",
+            program,
+            verdict(assessment),
+            decider.unwrap_or(""),
+            details.steps,
+            details.score,
+            details.multiplicity
+        )?;
+        Ok(())
+    }
+}
+
+/// The name of the kind of an assessment.
+fn verdict(assessment: &Assessment) -> &'static str {
+    match assessment {
+        Assessment::NoProgress(_, _) => "no progress",
+        Assessment::HaltedIn(_) => "halted",
+        Assessment::NotHalted(_) => "not halted",
+        Assessment::Cycles { .. } => "cycles",
+        Assessment::TranslatedCycles { .. } => "translated cycles",
+        Assessment::QuasiHalts { .. } => "quasihalts",
+        Assessment::BackwardRefuted { .. } => "backward refuted",
+        Assessment::SegmentRefuted { .. } => "segment refuted",
+        Assessment::HaltUnreachable(_) => "halt unreachable",
+        Assessment::Bounces(_) => "bounces",
+        Assessment::Counts(_) => "counts",
+        Assessment::ClosedLanguage(_) => "closed language",
+        Assessment::Reduced(_) => "reduced",
+    }
+}
+
+/// Reads the entries of a log.
+pub struct LogReader<R> {
+    lines: Lines<R>,
+    line: usize,
+}
+
+impl<R> LogReader<R>
+where
+    R: BufRead,
+{
+    /// Create a reader for a log. The header is read and checked immediately.
+    pub fn new(input: R) -> Result<Self, LogError> {
+        let mut lines = input.lines();
+        match lines.next().transpose()? {
+            Some(header) if header == HEADER => Ok(Self { lines, line: 1 }),
+            _ => Err(LogError::MissingHeader),
+        }
+    }
+
+    fn parse(&self, line: &str) -> Result<Entry, LogError> {
+        let malformed = || LogError::Malformed(self.line);
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 6 {
+            return Err(malformed());
+        }
+        Ok(Entry {
+            program: fields[0].to_string(),
+            verdict: fields[1].to_string(),
+            decider: Some(fields[2])
+                .filter(|decider| !decider.is_empty())
+                .map(str::to_string),
+            steps: fields[3].parse().map_err(|_| malformed())?,
+            score: fields[4].parse().map_err(|_| malformed())?,
+            multiplicity: fields[5].parse().map_err(|_| malformed())?,
+        })
+    }
+}
+
+impl<R> Iterator for LogReader<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Entry, LogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next().map(|line| {
+            self.line += 1;
+            self.parse(&line?)
+        })
+    }
+}
+
+/// The problems that can occur when reading or writing a log.
+#[derive(Debug)]
+pub enum LogError {
+    /// Reading or writing failed
+    Io(io::Error),
+    /// The log does not start with the header
+    MissingHeader,
+    /// The line with this number, starting at 1 for the header, is not an entry
+    Malformed(usize),
+}
+
+impl From<io::Error> for LogError {
+    fn from(error: io::Error) -> Self {
+        LogError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{CompleteProgram, Details, Footprint, Format};
+
+    fn details(steps: u128) -> Details {
+        Details {
+            steps,
+            score: 4,
+            multiplicity: 3,
+            footprint: Footprint::default(),
+        }
+    }
+
+    #[test]
+    fn logs_can_be_written_and_read() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+        let mut output = Vec::new();
+        let mut writer = LogWriter::new(&mut output).unwrap();
+        writer
+            .write(
+                &program.display_as(Format::Standard),
+                &Assessment::HaltedIn(details(6)),
+            )
+            .unwrap();
+        writer
+            .write_verdict(
+                &program,
+                &Verdict {
+                    decider: Some("cycle"),
+                    assessment: Assessment::Cycles {
+                        preperiod: 1,
+                        period: 2,
+                        details: details(3),
+                    },
+                    certificate: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            "program,verdict,decider,steps,score,multiplicity\n1RB1LB_1LA1RZ,halted,,6,4,3\n1R1 1L1 1L0   H,cycles,cycle,3,4,3\n",
+            String::from_utf8(output.clone()).unwrap()
+        );
+
+        let entries: Vec<Entry> = LogReader::new(output.as_slice())
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            vec![
+                Entry {
+                    program: "1RB1LB_1LA1RZ".to_string(),
+                    verdict: "halted".to_string(),
+                    decider: None,
+                    steps: 6,
+                    score: 4,
+                    multiplicity: 3
+                },
+                Entry {
+                    program: "1R1 1L1 1L0   H".to_string(),
+                    verdict: "cycles".to_string(),
+                    decider: Some("cycle".to_string()),
+                    steps: 3,
+                    score: 4,
+                    multiplicity: 3
+                }
+            ],
+            entries
+        );
+    }
+
+    #[test]
+    fn malformed_logs_are_rejected() {
+        assert!(matches!(
+            LogReader::new("1RB1LB_1LA1RZ,halted,,6,4,3\n".as_bytes()),
+            Err(LogError::MissingHeader)
+        ));
+
+        let mut reader = LogReader::new(
+            "program,verdict,decider,steps,score,multiplicity\n1RB,halted,,six,4,3\n".as_bytes(),
+        )
+        .unwrap();
+        assert!(matches!(reader.next(), Some(Err(LogError::Malformed(2)))));
+        assert!(reader.next().is_none());
+    }
+}
@@ -6,8 +6,22 @@
 //! * How many subjects halted.
 //! * How many subjects didn't halt within the alloted running time.
 //! * How many subjects got stuck.
+//! * How many subjects are proven to never halt.
+//! * How many subjects each decider settled.
 //! * What is a current sigma champion
 //! * What is a current s champion
+//! * What is a current space champion, i.e. the subject that visited the most cells before it halted
+//! * What is a current beeping busy beaver champion, i.e. the subject that quasihalts after the most steps. A subject that
+//!   halts quasihalts at the step it halts, so halted subjects compete as well.
+//!
+//! When the report is updated with the programs of the subjects, each champion retains its programs as text. Ties are retained
+//! up to a limit.
+//!
+//! For downstream tools a report can be written as JSON and read back, and the assessment of every subject can be logged as
+//! comma separated values with a [`LogWriter`] and read back with a [`LogReader`].
+//!
+//! A report can also summarize a random sample of Turing machines. In that case every subject counts once, regardless of its
+//! multiplicity, and the report can estimate the proportions of the population it was sampled from.
 //!
 //! The following code is representative for how to gather statistics
 //!
@@ -20,39 +34,95 @@
 //!     report.update_with(&assessment);
 //! }
 //! ```
+mod json;
+mod log;
+
+pub use json::JsonError;
+pub use log::{Entry, LogError, LogReader, LogWriter, HEADER};
+
+use crate::decider::Verdict;
 use crate::turing::{Assessment, Details};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+/// The z-score of a 95% confidence interval.
+const Z: f64 = 1.96;
+
+/// The number of tied programs a champion retains by default.
+const TIES: usize = 10;
 
 /// Summary of information about the Turing machines under consideration.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Report {
+    sampled: bool,
     subjects: usize,
     total: usize,
     halted: usize,
     indeterminate: usize,
     stuck: usize,
+    non_halting: usize,
+    decided: BTreeMap<String, usize>,
+    halted_steps: u128,
+    halted_squared_steps: u128,
     sigma_champion: Option<Champion>,
     s_champion: Option<Champion>,
+    bbb_champion: Option<Champion>,
+    space_champion: Option<Champion>,
+    ties: usize,
 }
 
 impl Report {
     /// Create an empty report
     pub fn new() -> Self {
         Self {
+            sampled: false,
             subjects: 0,
             total: 0,
             halted: 0,
             indeterminate: 0,
             stuck: 0,
+            non_halting: 0,
+            decided: BTreeMap::new(),
+            halted_steps: 0,
+            halted_squared_steps: 0,
             s_champion: None,
             sigma_champion: None,
+            bbb_champion: None,
+            space_champion: None,
+            ties: TIES,
+        }
+    }
+
+    /// Retain at most `ties` programs per champion.
+    pub fn with_ties(self, ties: usize) -> Self {
+        Self { ties, ..self }
+    }
+
+    /// Create an empty report for a random sample of Turing machines. Each subject counts once, regardless of its multiplicity.
+    pub fn sampling() -> Self {
+        Self {
+            sampled: true,
+            ..Self::new()
         }
     }
 
     /// Update the report with the assessment of the progress of a Turing machine.
     pub fn update_with(&mut self, assessment: &Assessment) {
+        self.assess(assessment, None);
+    }
+
+    /// Update the report with the assessment of the progress of a Turing machine, together with the program it ran.
+    ///
+    /// The program is retained as text when it is a champion, so pass it in the format it should be reported in, e.g.
+    /// `program.display_as(Format::Standard)`.
+    pub fn update_with_program(&mut self, program: &dyn Display, assessment: &Assessment) {
+        self.assess(assessment, Some(program));
+    }
+
+    fn assess(&mut self, assessment: &Assessment, program: Option<&dyn Display>) {
         match assessment {
             Assessment::HaltedIn(details) => {
-                self.halted(*details);
+                self.record_halted(*details, program);
             }
             Assessment::NoProgress(_reason, details) => {
                 self.stuck(*details);
@@ -60,56 +130,202 @@ impl Report {
             Assessment::NotHalted(details) => {
                 self.indeterminated(*details);
             }
+            Assessment::QuasiHalts { details, .. } => {
+                self.record_quasihalted(*details, program);
+            }
+            Assessment::Cycles { details, .. }
+            | Assessment::TranslatedCycles { details, .. }
+            | Assessment::BackwardRefuted { details, .. }
+            | Assessment::HaltUnreachable(details)
+            | Assessment::Bounces(details)
+            | Assessment::Counts(details)
+            | Assessment::ClosedLanguage(details)
+            | Assessment::Reduced(details)
+            | Assessment::SegmentRefuted { details, .. } => {
+                self.non_halting(*details);
+            }
         };
     }
 
+    /// Update the report with a verdict, counting the subject for the decider that settled it.
+    pub fn update_with_verdict(&mut self, verdict: &Verdict) {
+        self.update_with(&verdict.assessment);
+        self.decided(verdict);
+    }
+
+    /// Update the report with a verdict, together with the program it is about.
+    pub fn update_with_program_verdict(&mut self, program: &dyn Display, verdict: &Verdict) {
+        self.update_with_program(program, &verdict.assessment);
+        self.decided(verdict);
+    }
+
+    fn decided(&mut self, verdict: &Verdict) {
+        if let Some(decider) = verdict.decider {
+            let weight = self.weight(verdict.assessment.details());
+            *self.decided.entry(decider.to_string()).or_insert(0) += weight;
+        }
+    }
+
+    /// The number of subjects that a decider settled.
+    pub fn decided_by(&self, decider: &str) -> usize {
+        self.decided.get(decider).copied().unwrap_or(0)
+    }
+
     /// Update the report with the details of a **halted** Turing machine.
     pub fn halted(&mut self, details: Details) {
+        self.record_halted(details, None);
+    }
+
+    fn record_halted(&mut self, details: Details, program: Option<&dyn Display>) {
+        let weight = self.weight(&details);
         self.subjects += 1;
-        self.total += details.multiplicity;
-        self.halted += details.multiplicity;
-        self.update_champion(details);
+        self.total += weight;
+        self.halted += weight;
+        // Machines can run for so long that their squared steps overflow, so the sums saturate instead.
+        let weight = weight as u128;
+        self.halted_steps = self
+            .halted_steps
+            .saturating_add(weight.saturating_mul(details.steps));
+        self.halted_squared_steps = self.halted_squared_steps.saturating_add(
+            weight
+                .saturating_mul(details.steps)
+                .saturating_mul(details.steps),
+        );
+        self.update_champion(details, program);
+    }
+
+    /// Update the report with the details of a Turing machine that **quasihalted**, i.e. that never halts but eventually stops
+    /// entering some of its states.
+    pub fn quasihalted(&mut self, details: Details) {
+        self.record_quasihalted(details, None);
+    }
+
+    fn record_quasihalted(&mut self, details: Details, program: Option<&dyn Display>) {
+        self.non_halting(details);
+        let contender = Contender::new(details, program, self.ties);
+        contender.challenge(&mut self.bbb_champion, |details| details.steps);
     }
 
     /// Update the report with the details of a Turing machine whose behaviour is **indeterminate**.
     pub fn indeterminated(&mut self, details: Details) {
+        let weight = self.weight(&details);
         self.subjects += 1;
-        self.total += details.multiplicity;
-        self.indeterminate += details.multiplicity;
+        self.total += weight;
+        self.indeterminate += weight;
     }
 
     /// Update the report with the details of a Turing machine that did not progess.
     pub fn stuck(&mut self, details: Details) {
+        let weight = self.weight(&details);
         self.subjects += 1;
-        self.total += details.multiplicity;
-        self.stuck += details.multiplicity;
-    }
-
-    fn update_champion(&mut self, details: Details) {
-        match &mut self.sigma_champion {
-            Some(reigning) => {
-                if details.score > reigning.details.score {
-                    reigning.update(details);
-                }
-                if details.score == reigning.details.score {
-                    reigning.tally();
-                }
-            }
-            None => {
-                self.sigma_champion = Some(Champion::new(details));
-            }
+        self.total += weight;
+        self.stuck += weight;
+    }
+
+    /// Update the report with the details of a Turing machine that is proven to **never halt**.
+    pub fn non_halting(&mut self, details: Details) {
+        let weight = self.weight(&details);
+        self.subjects += 1;
+        self.total += weight;
+        self.non_halting += weight;
+    }
+
+    /// Estimate the proportions of the population the subjects are drawn from, with 95% confidence intervals.
+    ///
+    /// Returns `None` when there are no subjects.
+    pub fn estimates(&self) -> Option<Estimates> {
+        if self.total == 0 {
+            return None;
+        }
+        let steps = if self.halted > 0 {
+            let n = self.halted as f64;
+            let mean = self.halted_steps as f64 / n;
+            let variance = if self.halted > 1 {
+                ((self.halted_squared_steps as f64 - n * mean * mean) / (n - 1.0)).max(0.0)
+            } else {
+                0.0
+            };
+            let margin = Z * (variance / n).sqrt();
+            Some(Estimate {
+                value: mean,
+                lower: mean - margin,
+                upper: mean + margin,
+            })
+        } else {
+            None
         };
-        match &mut self.s_champion {
-            Some(reigning) => {
-                if details.steps > reigning.details.steps {
-                    reigning.update(details);
-                }
-                if details.steps == reigning.details.steps {
-                    reigning.tally();
-                }
+        Some(Estimates {
+            halted: Estimate::proportion(self.halted, self.total),
+            indeterminate: Estimate::proportion(self.indeterminate, self.total),
+            stuck: Estimate::proportion(self.stuck, self.total),
+            non_halting: Estimate::proportion(self.non_halting, self.total),
+            steps,
+        })
+    }
+
+    fn weight(&self, details: &Details) -> usize {
+        if self.sampled {
+            1
+        } else {
+            details.multiplicity
+        }
+    }
+
+    fn update_champion(&mut self, details: Details, program: Option<&dyn Display>) {
+        let contender = Contender::new(details, program, self.ties);
+        contender.challenge(&mut self.sigma_champion, |details| details.score as u128);
+        contender.challenge(&mut self.s_champion, |details| details.steps);
+        contender.challenge(&mut self.space_champion, |details| {
+            details.footprint.cells as u128
+        });
+        // A machine that halts also quasihalts, at the step it halts.
+        contender.challenge(&mut self.bbb_champion, |details| details.steps);
+    }
+
+    /// The champions of the report, by name. Categories without a champion are left out.
+    pub fn champions(&self) -> Vec<(&'static str, &Champion)> {
+        [
+            ("sigma", &self.sigma_champion),
+            ("s", &self.s_champion),
+            ("space", &self.space_champion),
+            ("bbb", &self.bbb_champion),
+        ]
+        .iter()
+        .filter_map(|(name, champion)| champion.as_ref().map(|champion| (*name, champion)))
+        .collect()
+    }
+}
+
+/// A Turing machine that challenges the champions of a report.
+struct Contender<'a> {
+    details: Details,
+    program: Option<&'a dyn Display>,
+    ties: usize,
+}
+
+impl<'a> Contender<'a> {
+    fn new(details: Details, program: Option<&'a dyn Display>, ties: usize) -> Self {
+        Self {
+            details,
+            program,
+            ties,
+        }
+    }
+
+    /// Challenge a champion, comparing a certain measure. The contender takes over when it out performs the champion.
+    fn challenge<F>(&self, champion: &mut Option<Champion>, measure: F)
+    where
+        F: Fn(&Details) -> u128,
+    {
+        match champion {
+            Some(reigning) if measure(&self.details) < measure(&reigning.details) => {}
+            Some(reigning) if measure(&self.details) == measure(&reigning.details) => {
+                reigning.tally(self.program, self.ties);
             }
-            None => {
-                self.s_champion = Some(Champion::new(details));
+            _ => {
+                let mut contender = Champion::new(self.details);
+                contender.retain(self.program, self.ties);
+                *champion = Some(contender);
             }
         };
     }
@@ -121,25 +337,275 @@ impl Default for Report {
     }
 }
 
+/// Estimates of the population a report is sampled from.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Estimates {
+    /// The proportion of Turing machines that halt
+    pub halted: Estimate,
+    /// The proportion of Turing machines whose behaviour is indeterminate
+    pub indeterminate: Estimate,
+    /// The proportion of Turing machines that did not progress
+    pub stuck: Estimate,
+    /// The proportion of Turing machines that are proven to never halt
+    pub non_halting: Estimate,
+    /// The mean number of steps of the Turing machines that halt, if any halted
+    pub steps: Option<Estimate>,
+}
+
+/// An estimate of a quantity, together with a 95% confidence interval.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Estimate {
+    /// The estimated value
+    pub value: f64,
+    /// The lower bound of the confidence interval
+    pub lower: f64,
+    /// The upper bound of the confidence interval
+    pub upper: f64,
+}
+
+impl Estimate {
+    /// Estimate a proportion from `successes` out of `trials`, using the [Wilson score interval](https://en.wikipedia.org/wiki/Binomial_proportion_confidence_interval#Wilson_score_interval).
+    pub fn proportion(successes: usize, trials: usize) -> Self {
+        let n = trials as f64;
+        let p = successes as f64 / n;
+        let denominator = 1.0 + Z * Z / n;
+        let center = (p + Z * Z / (2.0 * n)) / denominator;
+        let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+        Self {
+            value: p,
+            lower: center - margin,
+            upper: center + margin,
+        }
+    }
+}
+
 /// A *champion* is a Turing machine that out performs other Turing machines in a certain category.
+///
+/// Turing machines that perform equally well are tied. A champion counts them and retains the programs of some of them.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Champion {
     details: Details,
     count: usize,
+    programs: Vec<String>,
 }
 
 impl Champion {
     /// Creates a new champion from its corresponding details
     pub fn new(details: Details) -> Self {
-        Self { details, count: 1 }
+        Self {
+            details,
+            count: 1,
+            programs: Vec::new(),
+        }
+    }
+
+    /// The details of the run of the champion.
+    pub fn details(&self) -> &Details {
+        &self.details
+    }
+
+    /// The number of Turing machines that are tied for this champion.
+    pub fn count(&self) -> usize {
+        self.count
     }
 
-    fn update(&mut self, details: Details) {
-        self.details = details;
-        self.count = 1;
+    /// The programs of the tied Turing machines that are retained.
+    pub fn programs(&self) -> &[String] {
+        &self.programs
     }
 
-    fn tally(&mut self) {
+    fn tally(&mut self, program: Option<&dyn Display>, ties: usize) {
         self.count += 1;
+        self.retain(program, ties);
+    }
+
+    fn retain(&mut self, program: Option<&dyn Display>, ties: usize) {
+        if let Some(program) = program.filter(|_| self.programs.len() < ties) {
+            self.programs.push(program.to_string());
+        }
+    }
+}
+
+impl Display for Champion {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "steps={}, score={}, cells={}, count={}",
+            self.details.steps, self.details.score, self.details.footprint.cells, self.count
+        )?;
+        for program in &self.programs {
+            write!(formatter, "\n  {}", program)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{Footprint, State};
+
+    fn details(steps: u128, multiplicity: usize) -> Details {
+        Details {
+            steps,
+            score: 0,
+            multiplicity,
+            footprint: Footprint::default(),
+        }
+    }
+
+    #[test]
+    fn sampled_reports_count_every_subject_once() {
+        let mut report = Report::sampling();
+
+        report.halted(details(3, 7));
+        report.indeterminated(details(100, 5));
+
+        let estimates = report.estimates().unwrap();
+        assert_eq!(0.5, estimates.halted.value);
+        assert_eq!(0.5, estimates.indeterminate.value);
+    }
+
+    #[test]
+    fn estimates_have_confidence_intervals() {
+        let mut report = Report::sampling();
+        for steps in 0..100 {
+            if steps % 4 == 0 {
+                report.halted(details(steps, 1));
+            } else {
+                report.indeterminated(details(steps, 1));
+            }
+        }
+
+        let estimates = report.estimates().unwrap();
+
+        assert_eq!(0.25, estimates.halted.value);
+        assert!(estimates.halted.lower < 0.25 && 0.25 < estimates.halted.upper);
+        assert!((estimates.halted.lower - 0.1754).abs() < 0.001);
+        assert!((estimates.halted.upper - 0.3430).abs() < 0.001);
+        let steps = estimates.steps.unwrap();
+        assert_eq!(48.0, steps.value);
+        assert!(steps.lower < 48.0 && 48.0 < steps.upper);
+    }
+
+    #[test]
+    fn cycling_machines_are_not_indeterminate() {
+        let mut report = Report::new();
+
+        report.update_with(&Assessment::Cycles {
+            preperiod: 0,
+            period: 2,
+            details: details(2, 3),
+        });
+
+        assert_eq!(3, report.non_halting);
+        assert_eq!(0, report.indeterminate);
+    }
+
+    #[test]
+    fn verdicts_are_counted_per_decider() {
+        let mut report = Report::new();
+
+        report.update_with_verdict(&Verdict {
+            decider: Some("cycle"),
+            assessment: Assessment::Cycles {
+                preperiod: 0,
+                period: 2,
+                details: details(2, 3),
+            },
+            certificate: None,
+        });
+        report.update_with_verdict(&Verdict {
+            decider: None,
+            assessment: Assessment::NotHalted(details(100, 5)),
+            certificate: None,
+        });
+
+        assert_eq!(3, report.decided_by("cycle"));
+        assert_eq!(0, report.decided_by("bouncer"));
+        assert_eq!(5, report.indeterminate);
+    }
+
+    #[test]
+    fn quasihalting_machines_compete_for_the_beeping_busy_beaver() {
+        let mut report = Report::new();
+
+        report.halted(details(6, 1));
+        report.update_with(&Assessment::QuasiHalts {
+            omitted: vec![State::Number(1)],
+            details: details(9, 1),
+        });
+        report.update_with(&Assessment::QuasiHalts {
+            omitted: vec![State::Number(0)],
+            details: details(9, 1),
+        });
+
+        assert_eq!(2, report.non_halting);
+        let champion = |champion: &Option<Champion>| champion.as_ref().map(|c| c.details);
+        assert_eq!(Some(details(6, 1)), champion(&report.s_champion));
+        assert_eq!(Some(details(9, 1)), champion(&report.bbb_champion));
+    }
+
+    #[test]
+    fn halted_machines_compete_for_the_most_cells_visited() {
+        let mut report = Report::new();
+        let visiting = |steps: u128, cells: usize| Details {
+            footprint: Footprint {
+                cells,
+                leftmost: 0,
+                rightmost: cells as i128 - 1,
+            },
+            ..details(steps, 1)
+        };
+
+        report.halted(visiting(6, 4));
+        report.halted(visiting(8, 3));
+        report.non_halting(visiting(100, 50));
+
+        let champion = |champion: &Option<Champion>| champion.as_ref().map(|c| c.details);
+        assert_eq!(Some(visiting(8, 3)), champion(&report.s_champion));
+        assert_eq!(Some(visiting(6, 4)), champion(&report.space_champion));
+    }
+
+    #[test]
+    fn champions_retain_their_programs_up_to_a_limit() {
+        let mut report = Report::new().with_ties(2);
+
+        report.update_with_program(&"1RB1LB_1LA1RZ", &Assessment::HaltedIn(details(6, 1)));
+        report.update_with_program(&"1RB0LB_1LA1RZ", &Assessment::HaltedIn(details(6, 1)));
+        report.update_with(&Assessment::HaltedIn(details(6, 1)));
+        report.update_with_program(&"1RB1RZ_1LA0LA", &Assessment::HaltedIn(details(6, 1)));
+        report.update_with_program(&"1RB1RZ_---1LA", &Assessment::HaltedIn(details(5, 1)));
+
+        let champion = report.s_champion.as_ref().unwrap();
+        assert_eq!(4, champion.count());
+        assert_eq!(&["1RB1LB_1LA1RZ", "1RB0LB_1LA1RZ"], champion.programs());
+        assert_eq!(
+            "steps=6, score=0, cells=0, count=4\n  1RB1LB_1LA1RZ\n  1RB0LB_1LA1RZ",
+            champion.to_string()
+        );
+
+        report.update_with_program(&"1RB1LB_1LA1RZ", &Assessment::HaltedIn(details(7, 1)));
+
+        let champion = report.s_champion.as_ref().unwrap();
+        assert_eq!(1, champion.count());
+        assert_eq!(&["1RB1LB_1LA1RZ"], champion.programs());
+    }
+
+    #[test]
+    fn long_running_machines_do_not_overflow_the_statistics() {
+        let mut report = Report::new();
+
+        report.halted(details(u128::MAX / 2, 3));
+        report.halted(details(u128::MAX / 2, 3));
+
+        assert_eq!(u128::MAX, report.halted_steps);
+        assert_eq!(u128::MAX, report.halted_squared_steps);
+        assert!(report.estimates().unwrap().steps.is_some());
+    }
+
+    #[test]
+    fn empty_reports_have_no_estimates() {
+        assert_eq!(None, Report::sampling().estimates());
     }
 }
@@ -2,11 +2,15 @@
 use super::{
     program::{Action, Key, Lookup, Program},
     state::State,
-    symbol::Symbol,
-    tape::Tape,
+    tape::{CompoundTape, Footprint, Tape},
 };
+use std::convert::TryFrom;
 
 /// A Turing machine
+///
+/// Besides its configuration, a Turing machine keeps track of the number of steps it took and of the last step at which it
+/// entered each state.
+#[derive(Clone)]
 pub struct Machine<'a, T>
 where
     T: Tape + Sized,
@@ -14,6 +18,8 @@ where
     tape: T,
     program: &'a dyn Program,
     state: State,
+    steps: u128,
+    last_visits: Vec<Option<u128>>,
 }
 
 impl<'a, T> From<Machine<'a, T>> for (T, State, &'a dyn Program)
@@ -31,19 +37,59 @@ where
 {
     /// Create a Turing machine with a certain tape and program. Starts in state 0
     pub fn new(tape: T, program: &'a dyn Program) -> Self {
-        Self {
-            tape,
-            state: State::Number(0),
-            program,
-        }
+        Self::with(tape, State::Number(0), program)
     }
 
     /// Create a Turing machine with a certain tape, state and program.
     pub fn with(tape: T, state: State, program: &'a dyn Program) -> Self {
-        Self {
+        let mut machine = Self {
             tape,
             state,
             program,
+            steps: 0,
+            last_visits: Vec::new(),
+        };
+        machine.visit();
+        machine
+    }
+
+    /// The state the Turing machine is in.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// The tape of the Turing machine.
+    pub fn tape(&self) -> &T {
+        &self.tape
+    }
+
+    /// The program of the Turing machine.
+    pub fn program(&self) -> &'a dyn Program {
+        self.program
+    }
+
+    /// The number of steps the Turing machine took since it was created.
+    pub fn steps(&self) -> u128 {
+        self.steps
+    }
+
+    /// The last step at which the Turing machine entered a state, or `None` when it never did.
+    ///
+    /// The state the Turing machine was created in counts as entered at step 0.
+    pub fn last_visit(&self, state: State) -> Option<u128> {
+        match state {
+            State::Number(s) => self.last_visits.get(s as usize).copied().flatten(),
+            State::Halted => None,
+        }
+    }
+
+    fn visit(&mut self) {
+        if let State::Number(s) = self.state {
+            let s = s as usize;
+            if self.last_visits.len() <= s {
+                self.last_visits.resize(s + 1, None);
+            }
+            self.last_visits[s] = Some(self.steps);
         }
     }
 
@@ -59,6 +105,7 @@ where
                 Lookup::Indeterminate => Progress::Limbo,
                 Lookup::Determined(Action::Halt) => {
                     self.state = State::Halted;
+                    self.steps += 1;
                     Progress::Made
                 }
                 Lookup::Determined(Action::Do {
@@ -69,6 +116,8 @@ where
                     self.tape.write(symbol);
                     self.tape.move_to(&direction);
                     self.state = state;
+                    self.steps += 1;
+                    self.visit();
                     Progress::Made
                 }
             }
@@ -87,37 +136,78 @@ where
             } else {
                 return Assessment::NoProgress(
                     progress,
-                    Details {
-                        steps: steps_taken,
-                        score: 0,
-                        multiplicity: self.program.multiplicity(),
-                    },
+                    Details::from_machine(self, steps_taken, 0),
                 );
             }
         }
         if self.state.halted() {
-            Assessment::HaltedIn(Details {
-                steps: steps_taken,
-                score: self.score(),
-                multiplicity: self.program.multiplicity(),
-            })
+            Assessment::HaltedIn(Details::from_machine(self, steps_taken, self.score()))
         } else {
-            Assessment::NotHalted(Details {
-                steps: steps_taken,
-                score: 0,
-                multiplicity: self.program.multiplicity(),
-            })
+            Assessment::NotHalted(Details::from_machine(self, steps_taken, 0))
         }
     }
 
     /// The number of non blank symbols on the tape
     pub fn score(&self) -> usize {
-        self.tape.count(&Symbol::NonBlank)
+        self.tape.count()
+    }
+}
+
+impl<'a> Machine<'a, CompoundTape> {
+    /// Take several steps until either the maximum number of steps is attained or the machine halted, skipping runs.
+    ///
+    /// When the machine would move through a run of identical symbols without changing state, the whole run is rewritten in
+    /// one go. The steps of a skipped run are added in bulk, and a run is only skipped as far as the maximum number of steps
+    /// allows, so the assessment is the same as that of `run`.
+    pub fn run_skipping(&mut self, maximum_steps: u128) -> Assessment {
+        let mut steps_taken: u128 = 0u128;
+        while !self.state.halted() && steps_taken < maximum_steps {
+            if let Some(steps) = self.skip(maximum_steps - steps_taken) {
+                steps_taken += steps;
+                continue;
+            }
+            let progress = self.step();
+            if matches!(progress, Progress::Made) {
+                steps_taken += 1;
+            } else {
+                return Assessment::NoProgress(
+                    progress,
+                    Details::from_machine(self, steps_taken, 0),
+                );
+            }
+        }
+        if self.state.halted() {
+            Assessment::HaltedIn(Details::from_machine(self, steps_taken, self.score()))
+        } else {
+            Assessment::NotHalted(Details::from_machine(self, steps_taken, 0))
+        }
+    }
+
+    /// Sweep the run under the head when the machine stays in its state. Returns the number of steps taken.
+    fn skip(&mut self, limit: u128) -> Option<u128> {
+        let key = Key {
+            state: self.state,
+            symbol: self.tape.read(),
+        };
+        match self.program.lookup(&key) {
+            Lookup::Determined(Action::Do {
+                symbol,
+                direction,
+                state,
+            }) if state == self.state => {
+                let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+                let steps = self.tape.sweep(symbol, &direction, limit) as u128;
+                self.steps += steps;
+                self.visit();
+                Some(steps)
+            }
+            _ => None,
+        }
     }
 }
 
 /// The possibilities when a Turing machine takes a step
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Progress {
     /// The Turing machine could have halted.
     Halted,
@@ -138,6 +228,78 @@ pub enum Assessment {
     HaltedIn(Details),
     /// The Turing machine is still running after a maximum number of steps is reached
     NotHalted(Details),
+    /// The configuration of the Turing machine repeats, so it never halts
+    Cycles {
+        /// The number of steps before the Turing machine enters the cycle
+        preperiod: u128,
+        /// The number of steps in a single cycle
+        period: u128,
+        /// Details of the run up to the detection of the cycle
+        details: Details,
+    },
+    /// The Turing machine repeats the same steps, shifted along the tape into blank cells, so it never halts
+    TranslatedCycles {
+        /// The number of steps before the first repetition starts
+        preperiod: u128,
+        /// The number of steps in a single repetition
+        period: u128,
+        /// The number of cells a single repetition shifts the tape head, negative when drifting to the left
+        distance: i128,
+        /// Details of the run up to the detection of the repetition
+        details: Details,
+    },
+    /// The Turing machine never halts, but after a certain step it never enters some of its states again
+    QuasiHalts {
+        /// The states that are never entered again
+        omitted: Vec<State>,
+        /// Details of the run, where the steps include the last step taken in one of the omitted states
+        details: Details,
+    },
+    /// Reasoning backwards from the halting transitions shows that the Turing machine never halts
+    BackwardRefuted {
+        /// The length of the longest chain of configurations that lead to a halting transition
+        depth: usize,
+        /// Details of the run before reasoning backwards
+        details: Details,
+    },
+    /// No configuration within a segment of the tape leads from the start to a halting transition
+    SegmentRefuted {
+        /// The width of the segment that sufficed
+        width: usize,
+        /// Details of the program
+        details: Details,
+    },
+    /// The Turing machine never halts, because no halting transition is reachable in the graph of its program
+    HaltUnreachable(Details),
+    /// The Turing machine bounces back and forth over a tape that grows linearly, so it never halts
+    Bounces(Details),
+    /// The Turing machine counts in binary, so it never halts
+    Counts(Details),
+    /// The configurations of the Turing machine stay within a regular language without halting configurations
+    ClosedLanguage(Details),
+    /// The halting configurations of the Turing machine form a regular language that does not contain the start configuration
+    Reduced(Details),
+}
+
+impl Assessment {
+    /// The details of the run or the program that was assessed.
+    pub fn details(&self) -> &Details {
+        match self {
+            Assessment::NoProgress(_, details)
+            | Assessment::HaltedIn(details)
+            | Assessment::NotHalted(details)
+            | Assessment::HaltUnreachable(details)
+            | Assessment::Bounces(details)
+            | Assessment::Counts(details)
+            | Assessment::ClosedLanguage(details)
+            | Assessment::Reduced(details) => details,
+            Assessment::Cycles { details, .. }
+            | Assessment::TranslatedCycles { details, .. }
+            | Assessment::QuasiHalts { details, .. }
+            | Assessment::BackwardRefuted { details, .. }
+            | Assessment::SegmentRefuted { details, .. } => details,
+        }
+    }
 }
 
 /// Details of a run
@@ -149,31 +311,63 @@ pub struct Details {
     pub score: usize,
     /// The multiplicity of the program that was used to run the Turing machine
     pub multiplicity: usize,
+    /// The part of the tape that was visited, unknown when the cells count is zero
+    pub footprint: Footprint,
+}
+
+impl Details {
+    /// The details of a Turing machine that took `steps` steps and scored `score`.
+    pub fn from_machine<T>(machine: &Machine<T>, steps: u128, score: usize) -> Self
+    where
+        T: Tape,
+    {
+        Self {
+            steps,
+            score,
+            multiplicity: machine.program.multiplicity(),
+            footprint: machine.tape.footprint(),
+        }
+    }
+
+    /// The details of a program that was assessed without running it.
+    pub fn unsimulated(program: &dyn Program) -> Self {
+        Self {
+            steps: 0,
+            score: 0,
+            multiplicity: program.multiplicity(),
+            footprint: Footprint::default(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::turing::{direction::Direction, program::CompleteProgram, tape::SimpleTape};
+    use crate::turing::{
+        direction::Direction,
+        program::{CompleteProgram, Format},
+        symbol::Symbol,
+        tape::{CompoundTape, SimpleTape},
+    };
 
     #[test]
     fn a_simple_machine_can_be_run() {
         let mut program = CompleteProgram::new();
         program.insert(
-            (State::Number(0), Symbol::Blank),
-            (Symbol::NonBlank, Direction::Right, State::Number(1)),
+            (State::Number(0), Symbol::BLANK),
+            (Symbol::NON_BLANK, Direction::Right, State::Number(1)),
         );
         program.insert(
-            (State::Number(0), Symbol::NonBlank),
-            (Symbol::NonBlank, Direction::Left, State::Halted),
+            (State::Number(0), Symbol::NON_BLANK),
+            (Symbol::NON_BLANK, Direction::Left, State::Halted),
         );
         program.insert(
-            (State::Number(1), Symbol::Blank),
-            (Symbol::NonBlank, Direction::Left, State::Number(0)),
+            (State::Number(1), Symbol::BLANK),
+            (Symbol::NON_BLANK, Direction::Left, State::Number(0)),
         );
         program.insert(
-            (State::Number(1), Symbol::NonBlank),
-            (Symbol::NonBlank, Direction::Right, State::Halted),
+            (State::Number(1), Symbol::NON_BLANK),
+            (Symbol::NON_BLANK, Direction::Right, State::Halted),
         );
         let start = State::Number(0);
         let mut machine = Machine::with(SimpleTape::empty(), start, &program);
@@ -185,8 +379,74 @@ mod tests {
             Assessment::HaltedIn(Details {
                 steps: 3,
                 score: 2,
-                multiplicity: 1
+                multiplicity: 1,
+                footprint: Footprint {
+                    cells: 3,
+                    leftmost: -1,
+                    rightmost: 1
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn machines_can_run_programs_over_larger_alphabets() {
+        // The busy beaver champion with two states and three symbols.
+        let program = CompleteProgram::parse_as("1RB2LB1RZ_2LA2RB1LB", Format::Standard).unwrap();
+        let mut machine = Machine::new(SimpleTape::empty(), &program);
+
+        let assessment = machine.run(100);
+
+        assert_eq!(
+            assessment,
+            Assessment::HaltedIn(Details {
+                steps: 38,
+                score: 9,
+                multiplicity: 1,
+                footprint: Footprint {
+                    cells: 9,
+                    leftmost: -6,
+                    rightmost: 2
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn machines_remember_when_they_last_entered_a_state() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+        let mut machine = Machine::new(SimpleTape::empty(), &program);
+
+        assert_eq!(Some(0), machine.last_visit(State::Number(0)));
+        assert_eq!(None, machine.last_visit(State::Number(1)));
+
+        machine.run(10);
+
+        assert_eq!(6, machine.steps());
+        assert_eq!(Some(4), machine.last_visit(State::Number(0)));
+        assert_eq!(Some(5), machine.last_visit(State::Number(1)));
+    }
+
+    #[test]
+    fn long_sweeps_are_skipped() {
+        let program: CompleteProgram = "1R1 1L0 1R1 0L1".parse().unwrap();
+        let mut machine = Machine::new(CompoundTape::empty(), &program);
+
+        let assessment = machine.run_skipping(1_000_000_000_000);
+
+        assert_eq!(
+            assessment,
+            Assessment::NotHalted(Details {
+                steps: 1_000_000_000_000,
+                score: 0,
+                multiplicity: 1,
+                footprint: Footprint {
+                    cells: 1_000_000_000_001,
+                    leftmost: 0,
+                    rightmost: 1_000_000_000_000
+                }
             })
         );
+        assert_eq!(machine.tape().position(), 1_000_000_000_000);
     }
 }
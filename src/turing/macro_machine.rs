@@ -0,0 +1,388 @@
+//! Simulate a Turing machine with blocks of cells as symbols.
+//!
+//! A *macro machine* groups the tape in blocks of `k` cells. The head is always at the edge of a block, facing the block it will
+//! enter next. Entering a block, the underlying machine is run within the block until the head leaves it. The resulting block,
+//! the side the head leaves from, the new state and the number of steps taken, form a *macro transition*, which is cached.
+//!
+//! The tape is kept as runs of identical blocks. When a macro transition moves the head through a block in the direction it was
+//! facing, without changing state, the same transition applies to every block of the run. The whole run is then rewritten at
+//! once, while the number of underlying steps is still counted exactly.
+use super::{
+    direction::Direction,
+    machine::{Assessment, Details, Progress},
+    program::{Action, Key, Lookup, Program},
+    state::State,
+    symbol::Symbol,
+    tape::Footprint,
+};
+use std::collections::{HashMap, HashSet};
+
+/// A Turing machine that simulates an underlying program on blocks of cells.
+pub struct MacroMachine<'a> {
+    program: &'a dyn Program,
+    k: usize,
+    state: State,
+    facing: Direction,
+    left: Vec<(Vec<Symbol>, u128)>,
+    right: Vec<(Vec<Symbol>, u128)>,
+    steps: u128,
+    transitions: HashMap<(State, Direction, Vec<Symbol>), Transition>,
+}
+
+/// What happens when the head enters a block.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Transition {
+    /// The head leaves the block
+    Leaves {
+        block: Vec<Symbol>,
+        direction: Direction,
+        state: State,
+        steps: u128,
+    },
+    /// The machine halts within the block
+    Halts { block: Vec<Symbol>, steps: u128 },
+    /// The machine never leaves the block
+    Loops,
+    /// The program does not determine what happens
+    Stuck(Progress),
+}
+
+impl<'a> MacroMachine<'a> {
+    /// Create a macro machine with blocks of `k` cells for a program. Starts in state 0 on a blank tape.
+    pub fn new(program: &'a dyn Program, k: usize) -> Self {
+        Self {
+            program,
+            k: k.max(1),
+            state: State::Number(0),
+            facing: Direction::Right,
+            left: Vec::new(),
+            right: Vec::new(),
+            steps: 0,
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// The state the underlying Turing machine is in.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// The number of steps the underlying Turing machine took.
+    pub fn steps(&self) -> u128 {
+        self.steps
+    }
+
+    /// The number of non blank symbols on the tape
+    pub fn score(&self) -> usize {
+        self.left
+            .iter()
+            .chain(self.right.iter())
+            .map(|(block, count)| {
+                let ones = block.iter().filter(|symbol| !symbol.is_blank()).count();
+                ones * *count as usize
+            })
+            .sum()
+    }
+
+    /// Run until either the underlying Turing machine halted or the next block would take it past the maximum number of steps.
+    ///
+    /// The underlying Turing machine never takes more than the maximum number of steps. So it only halts when `Machine::run`
+    /// with the same maximum halts, but it can stop a few steps short of the maximum when it does not.
+    pub fn run(&mut self, maximum_steps: u128) -> Assessment {
+        while self.steps < maximum_steps {
+            let remaining = maximum_steps - self.steps;
+            let (block, count) = self.take(self.facing);
+            let transition = self.transition(block.clone());
+            let steps = match transition {
+                Transition::Leaves { steps, .. } | Transition::Halts { steps, .. } => steps,
+                Transition::Loops | Transition::Stuck(_) => 0,
+            };
+            if steps > remaining {
+                self.put(self.facing, block, count.unwrap_or(0));
+                break;
+            }
+            match transition {
+                Transition::Leaves {
+                    block: written,
+                    direction,
+                    state,
+                    steps,
+                } => {
+                    let behind = opposite(self.facing);
+                    match count {
+                        Some(count) if direction == self.facing && state == self.state => {
+                            // Only skip as many blocks as fit within the maximum number of steps.
+                            let skipped = count.min(remaining / steps.max(1));
+                            if skipped < count {
+                                self.put(self.facing, block, count - skipped);
+                            }
+                            self.put(behind, written, skipped);
+                            self.steps += steps * skipped;
+                        }
+                        _ => {
+                            if let Some(count) = count.filter(|count| *count > 1) {
+                                self.put(self.facing, block, count - 1);
+                            }
+                            let side = if direction == self.facing {
+                                behind
+                            } else {
+                                self.facing
+                            };
+                            self.put(side, written, 1);
+                            self.facing = direction;
+                            self.state = state;
+                            self.steps += steps;
+                        }
+                    }
+                }
+                Transition::Halts {
+                    block: written,
+                    steps,
+                } => {
+                    if let Some(count) = count.filter(|count| *count > 1) {
+                        self.put(self.facing, block, count - 1);
+                    }
+                    self.put(self.facing, written, 1);
+                    self.state = State::Halted;
+                    self.steps += steps;
+                    return Assessment::HaltedIn(self.details());
+                }
+                Transition::Loops => return Assessment::NotHalted(self.details()),
+                Transition::Stuck(progress) => {
+                    self.put(self.facing, block, count.unwrap_or(1));
+                    return Assessment::NoProgress(progress, self.details());
+                }
+            }
+        }
+        Assessment::NotHalted(self.details())
+    }
+
+    /// The footprint of the underlying Turing machine is not tracked.
+    fn details(&self) -> Details {
+        Details {
+            steps: self.steps,
+            score: self.score(),
+            multiplicity: self.program.multiplicity(),
+            footprint: Footprint::default(),
+        }
+    }
+
+    /// Take the block the head is facing on one side. The count is `None` for the blanks that continue forever.
+    fn take(&mut self, side: Direction) -> (Vec<Symbol>, Option<u128>) {
+        match self.side(side).pop() {
+            Some((block, count)) => (block, Some(count)),
+            None => (vec![Symbol::BLANK; self.k], None),
+        }
+    }
+
+    /// Put blocks on one side of the head, next to the head.
+    fn put(&mut self, side: Direction, block: Vec<Symbol>, count: u128) {
+        let runs = self.side(side);
+        match runs.last_mut() {
+            Some((last, n)) if *last == block => *n += count,
+            None if block.iter().all(|symbol| *symbol == Symbol::BLANK) => {}
+            _ => runs.push((block, count)),
+        }
+    }
+
+    fn side(&mut self, side: Direction) -> &mut Vec<(Vec<Symbol>, u128)> {
+        match side {
+            Direction::Left => &mut self.left,
+            Direction::Right => &mut self.right,
+        }
+    }
+
+    fn transition(&mut self, block: Vec<Symbol>) -> Transition {
+        let key = (self.state, self.facing, block);
+        if let Some(transition) = self.transitions.get(&key) {
+            return transition.clone();
+        }
+        let transition = self.compute(&key);
+        self.transitions.insert(key, transition.clone());
+        transition
+    }
+
+    /// Run the underlying Turing machine within a block, entering it while facing a direction.
+    fn compute(&self, (state, facing, block): &(State, Direction, Vec<Symbol>)) -> Transition {
+        let mut block = block.clone();
+        let mut state = *state;
+        let mut position: isize = match facing {
+            Direction::Right => 0,
+            Direction::Left => self.k as isize - 1,
+        };
+        let mut steps = 0;
+        let mut seen = HashSet::new();
+        while 0 <= position && position < self.k as isize {
+            if !seen.insert((state, position, block.clone())) {
+                return Transition::Loops;
+            }
+            let key = Key {
+                state,
+                symbol: block[position as usize],
+            };
+            match self.program.lookup(&key) {
+                Lookup::Unknown => return Transition::Stuck(Progress::Stuck),
+                Lookup::Indeterminate => return Transition::Stuck(Progress::Limbo),
+                Lookup::Determined(Action::Halt) => {
+                    return Transition::Halts {
+                        block,
+                        steps: steps + 1,
+                    }
+                }
+                Lookup::Determined(Action::Do {
+                    symbol,
+                    direction,
+                    state: next,
+                }) => {
+                    block[position as usize] = symbol;
+                    if next.halted() {
+                        return Transition::Halts {
+                            block,
+                            steps: steps + 1,
+                        };
+                    }
+                    position += match direction {
+                        Direction::Left => -1,
+                        Direction::Right => 1,
+                    };
+                    state = next;
+                    steps += 1;
+                }
+            }
+        }
+        Transition::Leaves {
+            block,
+            direction: if position < 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            },
+            state,
+            steps,
+        }
+    }
+}
+
+fn opposite(direction: Direction) -> Direction {
+    match direction {
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{CompleteProgram, IncompleteProgram, Machine, Programs, SimpleTape};
+
+    #[test]
+    fn macro_machines_agree_with_machines() {
+        for program in Programs::all(2) {
+            for maximum in [1, 2, 3, 4, 5, 6, 100] {
+                let mut machine = Machine::new(SimpleTape::empty(), &program);
+                let expected = machine.run(maximum);
+                for k in 1..=3 {
+                    let mut macro_machine = MacroMachine::new(&program, k);
+                    if let Assessment::HaltedIn(details) = expected {
+                        let details = Details {
+                            footprint: Footprint::default(),
+                            ..details
+                        };
+                        assert_eq!(Assessment::HaltedIn(details), macro_machine.run(maximum));
+                    } else {
+                        assert!(!matches!(
+                            macro_machine.run(maximum),
+                            Assessment::HaltedIn(_)
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn macro_machines_halt_on_actions_that_write_before_halting() {
+        for program in ["1R1 1L1 1L0 1LH", "1RH 1RH 1RH 1RH"] {
+            let program: CompleteProgram = program.parse().unwrap();
+            let mut machine = Machine::new(SimpleTape::empty(), &program);
+            let expected = match machine.run(100) {
+                Assessment::HaltedIn(details) => Assessment::HaltedIn(Details {
+                    footprint: Footprint::default(),
+                    ..details
+                }),
+                assessment => panic!("expected a halting machine, got {:?}", assessment),
+            };
+            for k in 1..=3 {
+                assert_eq!(expected, MacroMachine::new(&program, k).run(100));
+            }
+        }
+    }
+
+    #[test]
+    fn stuck_macro_machines_keep_the_whole_run() {
+        // Writes three ones and gets stuck on a run of two blocks.
+        let program: IncompleteProgram = "1R1 ??? 1R2 ??? 1L3 ??? ??? ???".parse().unwrap();
+        let mut macro_machine = MacroMachine::new(&program, 1);
+
+        let assessment = macro_machine.run(100);
+
+        assert!(matches!(
+            assessment,
+            Assessment::NoProgress(Progress::Limbo, _)
+        ));
+        assert_eq!(3, macro_machine.score());
+    }
+
+    #[test]
+    fn skipped_runs_stop_at_the_maximum_number_of_steps() {
+        // The fifth busy beaver champion, which sweeps over long runs of blocks.
+        let program: CompleteProgram = "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3   H 0L0".parse().unwrap();
+        let mut macro_machine = MacroMachine::new(&program, 3);
+        let mut machine = Machine::new(SimpleTape::empty(), &program);
+
+        let assessment = macro_machine.run(1_000_000);
+
+        assert!(matches!(assessment, Assessment::NotHalted(_)));
+        assert!(macro_machine.steps() <= 1_000_000);
+        // It stops short by less than a single block, which takes fewer steps than there are configurations within a block.
+        assert!(macro_machine.steps() > 1_000_000 - 5 * 3 * 8);
+        machine.run(macro_machine.steps());
+        assert_eq!(machine.score(), macro_machine.score());
+    }
+
+    #[test]
+    fn macro_machines_only_halt_within_the_maximum_number_of_steps() {
+        // The fifth busy beaver champion halts after 47_176_870 steps.
+        let program: CompleteProgram = "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3   H 0L0".parse().unwrap();
+        let mut macro_machine = MacroMachine::new(&program, 3);
+
+        let assessment = macro_machine.run(47_176_869);
+
+        assert!(matches!(assessment, Assessment::NotHalted(_)));
+        assert!(macro_machine.steps() <= 47_176_869);
+        assert!(matches!(
+            macro_machine.run(47_176_870),
+            Assessment::HaltedIn(_)
+        ));
+        assert_eq!(47_176_870, macro_machine.steps());
+    }
+
+    #[test]
+    fn runs_of_blocks_are_skipped() {
+        // The fifth busy beaver champion. Its halting action does not write, so one fewer non blank symbol is left.
+        let program: CompleteProgram = "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3   H 0L0".parse().unwrap();
+        let mut machine = MacroMachine::new(&program, 3);
+
+        let assessment = machine.run(50_000_000);
+
+        assert_eq!(
+            Assessment::HaltedIn(Details {
+                steps: 47_176_870,
+                score: 4097,
+                multiplicity: 1,
+                footprint: Footprint::default()
+            }),
+            assessment
+        );
+    }
+}
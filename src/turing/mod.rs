@@ -20,20 +20,32 @@
 //! let assessment = machine.run(50_000_000);
 //! println!("{:?}", assessment);
 //! ```
+//!
+//! Programs write the symbols `0` and `1` unless they are created or parsed with a larger alphabet, e.g. with
+//! `CompleteProgram::parse_with_symbols`. The blank symbol is always `0`.
+//!
+//! Turing machines that run for a long time can be simulated by a `MacroMachine`, which treats blocks of cells as single symbols
+//! and rewrites runs of identical blocks at once, while counting the steps of the underlying machine exactly.
 
 mod direction;
 mod machine;
+mod macro_machine;
 mod program;
+mod sample;
 mod state;
 mod symbol;
 mod tape;
+mod tree;
 
 pub use direction::{Direction, Directions};
 pub use machine::{Assessment, Details, Machine, Progress};
+pub use macro_machine::MacroMachine;
 pub use program::{
-    Action, Actions, CompleteProgram, CompletePrograms as Programs, IncompleteProgram, Key, Keys,
-    Lookup, Program,
+    Action, Actions, CompleteProgram, CompletePrograms as Programs, Format, Formatted,
+    IncompleteProgram, Key, Keys, Lookup, Program, Representative, Representatives,
 };
+pub use sample::Sampler;
 pub use state::{State, States};
 pub use symbol::{Symbol, Symbols};
-pub use tape::{CompoundTape, SimpleTape, Tape};
+pub use tape::{CompoundTape, Footprint, SimpleTape, Snapshot, Tape};
+pub use tree::TreeNormalForm;
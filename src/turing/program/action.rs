@@ -38,6 +38,48 @@ impl From<(Symbol, Direction, State)> for Action {
     }
 }
 
+impl Action {
+    /// Determine if this action halts the Turing machine. Besides `Action::Halt`, an action can write and move before it
+    /// enters the halted state.
+    pub fn halts(&self) -> bool {
+        match self {
+            Action::Halt => true,
+            Action::Do { state, .. } => state.halted(),
+        }
+    }
+
+    /// The position of this action in the order of `Actions::up_to`, see `Action::index_with_symbols`.
+    pub fn index(&self) -> Option<usize> {
+        self.index_with_symbols(2)
+    }
+
+    /// The position of this action in the order of `Actions::with_symbols`, for an alphabet of `symbols` symbols.
+    ///
+    /// Returns `None` for an action that writes and moves before it halts, because the actions of `Actions::with_symbols` only
+    /// halt with `Action::Halt`.
+    pub fn index_with_symbols(&self, symbols: u8) -> Option<usize> {
+        match self {
+            Action::Halt => Some(0),
+            Action::Do {
+                symbol,
+                direction,
+                state,
+            } => {
+                let s = match state {
+                    State::Number(s) => *s as usize,
+                    State::Halted => return None,
+                };
+                let b = symbol.digit() as usize;
+                let d = match direction {
+                    Direction::Left => 0,
+                    Direction::Right => 1,
+                };
+                Some(1 + 2 * symbols as usize * s + 2 * b + d)
+            }
+        }
+    }
+}
+
 impl Display for Action {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
@@ -55,17 +97,18 @@ impl FromStr for Action {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        if input.len() == 3 {
+        let characters: Vec<String> = input.chars().map(String::from).collect();
+        if characters.len() == 3 {
             match input {
                 "  H" => Ok(Action::Halt),
                 _ => {
-                    let symbol = input[0..1]
+                    let symbol = characters[0]
                         .parse::<Symbol>()
                         .map_err(ParseError::SymbolProblem)?;
-                    let direction = input[1..2]
+                    let direction = characters[1]
                         .parse::<Direction>()
                         .map_err(ParseError::DirectionProblem)?;
-                    let state = input[2..3]
+                    let state = characters[2]
                         .parse::<State>()
                         .map_err(ParseError::StateProblem)?;
 
@@ -77,7 +120,7 @@ impl FromStr for Action {
                 }
             }
         } else {
-            Err(ParseError::IncorrectLength(input.len()))
+            Err(ParseError::IncorrectLength(characters.len()))
         }
     }
 }
@@ -99,11 +142,15 @@ pub struct Actions {
 impl Actions {
     /// Create a iterator that iterates through a number of states up to a maximum.
     pub fn up_to(maximum: u8) -> Self {
+        Self::with_symbols(maximum, 2)
+    }
+
+    /// Create a iterator that iterates through a number of states up to a maximum, writing one of `symbols` symbols.
+    pub fn with_symbols(maximum: u8, symbols: u8) -> Self {
         let iterator =
-            once(Action::Halt).chain(States::non_halted_up_to(maximum).flat_map(|state| {
-                cartesian!(Symbols::all(), Directions::all()).map(move |tuple| {
-                    (tuple.0, tuple.1, state).into()
-                })
+            once(Action::Halt).chain(States::non_halted_up_to(maximum).flat_map(move |state| {
+                cartesian!(Symbols::up_to(symbols), Directions::all())
+                    .map(move |tuple| (tuple.0, tuple.1, state).into())
             }));
         Self {
             iterator: Box::new(iterator),
@@ -128,7 +175,7 @@ mod tests {
         assert_eq!(Ok(Action::Halt), "  H".parse());
         assert_eq!(
             Ok(Action::Do {
-                symbol: Symbol::Blank,
+                symbol: Symbol::BLANK,
                 direction: Direction::Left,
                 state: State::Number(2)
             }),
@@ -161,6 +208,31 @@ mod tests {
             ))),
             "0La".parse::<Action>()
         );
+        assert_eq!(Err(ParseError::IncorrectLength(2)), "1é".parse::<Action>());
+        assert_eq!(
+            Err(ParseError::DirectionProblem(
+                direction::ParseError::UnknownSymbol("é".to_owned())
+            )),
+            "1é1".parse::<Action>()
+        );
+    }
+
+    #[test]
+    fn actions_know_when_they_halt() {
+        assert!(Action::Halt.halts());
+        assert!("1LH".parse::<Action>().unwrap().halts());
+        assert!(!"1L1".parse::<Action>().unwrap().halts());
+    }
+
+    #[test]
+    fn actions_know_their_index() {
+        for (index, action) in Actions::up_to(3).enumerate() {
+            assert_eq!(Some(index), action.index());
+        }
+        for (index, action) in Actions::with_symbols(2, 3).enumerate() {
+            assert_eq!(Some(index), action.index_with_symbols(3));
+        }
+        assert_eq!(None, "1LH".parse::<Action>().unwrap().index());
     }
 
     #[test]
@@ -170,14 +242,14 @@ mod tests {
         assert_eq!(
             vec![
                 Action::Halt,
-                (Symbol::Blank, Direction::Left, State::Number(0)).into(),
-                (Symbol::Blank, Direction::Right, State::Number(0)).into(),
-                (Symbol::NonBlank, Direction::Left, State::Number(0)).into(),
-                (Symbol::NonBlank, Direction::Right, State::Number(0)).into(),
-                (Symbol::Blank, Direction::Left, State::Number(1)).into(),
-                (Symbol::Blank, Direction::Right, State::Number(1)).into(),
-                (Symbol::NonBlank, Direction::Left, State::Number(1)).into(),
-                (Symbol::NonBlank, Direction::Right, State::Number(1)).into(),
+                (Symbol::BLANK, Direction::Left, State::Number(0)).into(),
+                (Symbol::BLANK, Direction::Right, State::Number(0)).into(),
+                (Symbol::NON_BLANK, Direction::Left, State::Number(0)).into(),
+                (Symbol::NON_BLANK, Direction::Right, State::Number(0)).into(),
+                (Symbol::BLANK, Direction::Left, State::Number(1)).into(),
+                (Symbol::BLANK, Direction::Right, State::Number(1)).into(),
+                (Symbol::NON_BLANK, Direction::Left, State::Number(1)).into(),
+                (Symbol::NON_BLANK, Direction::Right, State::Number(1)).into(),
             ],
             actual
         )
@@ -1,37 +1,56 @@
 //! A complete program
+mod symmetry;
+
 use super::{
     action,
     action::{Action, Actions},
+    format,
+    format::{Format, Formatted},
     Key, Keys, Lookup, Program,
 };
-use cartesian::*;
 use std::{
     convert::Into,
     fmt,
     fmt::{Display, Formatter},
     iter::IntoIterator,
+    ops::Range,
     str::FromStr,
 };
+pub use symmetry::{Representative, Representatives};
 
 /// A complete program
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CompleteProgram {
+    symbols: u8,
     program: Vec<Action>,
 }
 
 impl Program for CompleteProgram {
     fn lookup(&self, key: &Key) -> Lookup {
-        match self.program.get(key.idx()) {
+        if key.symbol.digit() >= self.symbols {
+            return Lookup::Unknown;
+        }
+        match self.program.get(key.index(self.symbols)) {
             Some(action) => Lookup::Determined(*action),
             None => Lookup::Unknown,
         }
     }
+
+    fn symbols(&self) -> u8 {
+        self.symbols
+    }
 }
 
 impl CompleteProgram {
     /// Create an empty complete progam
     pub fn new() -> Self {
+        Self::with_symbols(2)
+    }
+
+    /// Create an empty complete program over an alphabet of `symbols` symbols.
+    pub fn with_symbols(symbols: u8) -> Self {
         Self {
+            symbols,
             program: Vec::new(),
         }
     }
@@ -43,7 +62,97 @@ impl CompleteProgram {
         A: Into<Action>,
     {
         let key = key.into();
-        self.program.insert(key.idx(), action.into());
+        self.program.insert(key.index(self.symbols), action.into());
+    }
+
+    /// Parse a program written in a certain format.
+    ///
+    /// In the standard format undefined transitions, i.e. `---`, are interpreted as halting.
+    pub fn parse_as(input: &str, format: Format) -> Result<Self, ParseError> {
+        match format {
+            Format::Native => input.parse(),
+            Format::Standard => {
+                let (symbols, transitions) =
+                    format::parse_standard(input).map_err(ParseError::StandardProblem)?;
+                let mut program = CompleteProgram::with_symbols(symbols);
+                for (key, action) in transitions {
+                    program.insert(key, action.unwrap_or(Action::Halt));
+                }
+                Ok(program)
+            }
+        }
+    }
+
+    /// Display this program in a certain format.
+    pub fn display_as(&self, format: Format) -> Formatted<'_, Self> {
+        Formatted {
+            program: self,
+            format,
+        }
+    }
+
+    /// Parse a program in the native format over an alphabet of `symbols` symbols.
+    ///
+    /// Parsing a program with `str::parse` assumes an alphabet of two symbols.
+    pub fn parse_with_symbols(input: &str, symbols: u8) -> Result<Self, ParseError> {
+        if input.is_empty() {
+            return Err(ParseError::NoInput);
+        }
+        if !input.is_ascii() {
+            return Err(ParseError::NonAscii);
+        }
+        let mut program = CompleteProgram::with_symbols(symbols);
+        let mut action_index = 0;
+        let mut index = 4 * action_index;
+        while index < input.len() && (index + 3) <= input.len() {
+            let action = input[index..(index + 3)]
+                .parse::<Action>()
+                .and_then(|action| match action {
+                    Action::Do { symbol, .. } if symbol.digit() >= symbols => {
+                        Err(action::ParseError::UnknownSymbol(symbol.to_string()))
+                    }
+                    action => Ok(action),
+                })
+                .map_err(|error| ParseError::ActionProblem(ActionProblemDetail { index, error }))?;
+            program.insert(Key::at(action_index, symbols), action);
+            action_index += 1;
+            index = 4 * action_index;
+        }
+        if action_index.is_multiple_of(symbols as usize) {
+            Ok(program)
+        } else {
+            Err(ParseError::InsufficientActions(action_index))
+        }
+    }
+
+    /// The number of states of this program.
+    pub fn states(&self) -> u8 {
+        (self.program.len() / self.symbols as usize) as u8
+    }
+
+    /// The program with `n` states at a certain index in the order of `CompletePrograms::all`.
+    pub fn from_index(n: u8, index: u128) -> Option<Self> {
+        Self::from_index_with_symbols(n, 2, index)
+    }
+
+    /// The program with `n` states over an alphabet of `symbols` symbols at a certain index in the order of
+    /// `CompletePrograms::all_with_symbols`.
+    pub fn from_index_with_symbols(n: u8, symbols: u8, index: u128) -> Option<Self> {
+        CompletePrograms::starting_at_with_symbols(n, symbols, index).next()
+    }
+
+    /// The index of this program in the order of `CompletePrograms::all_with_symbols`.
+    ///
+    /// Returns `None` when the index can not be represented, i.e. when the number of programs with this number of states, see
+    /// `CompletePrograms::size_with_symbols`, can not be represented. Programs with an action that writes and moves before it
+    /// halts are not enumerated, so they have no index either.
+    pub fn index(&self) -> Option<u128> {
+        let base = self.program.len() as u128 * 2 + 1;
+        self.program.iter().try_fold(0u128, |index, action| {
+            index
+                .checked_mul(base)?
+                .checked_add(action.index_with_symbols(self.symbols)? as u128)
+        })
     }
 }
 
@@ -55,8 +164,7 @@ impl Default for CompleteProgram {
 
 impl Display for CompleteProgram {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
-        let n = (self.program.len() / 2) as u8; // We assume only complete programs
-        let actions: Vec<String> = Keys::up_to(n)
+        let actions: Vec<String> = Keys::with_symbols(self.states(), self.symbols)
             .map(|k| self.lookup(&k))
             .map(|l| {
                 let ao: Option<Action> = l.into();
@@ -68,33 +176,22 @@ impl Display for CompleteProgram {
     }
 }
 
+impl<'a> Display for Formatted<'a, CompleteProgram> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self.format {
+            Format::Native => self.program.fmt(formatter),
+            Format::Standard => {
+                format::write_standard(formatter, self.program, self.program.states())
+            }
+        }
+    }
+}
+
 impl FromStr for CompleteProgram {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        if !input.is_empty() {
-            let mut program = CompleteProgram::new();
-            let mut action_index = 0;
-            let mut index = 4 * action_index;
-            while index < input.len() && (index + 3) <= input.len() {
-                let action = input[index..(index + 3)]
-                    .parse::<Action>()
-                    .map_err(|error| {
-                        ParseError::ActionProblem(ActionProblemDetail { index, error })
-                    })?;
-                let key: Key = action_index.into();
-                program.insert(key, action);
-                action_index += 1;
-                index = 4 * action_index;
-            }
-            if action_index % 2 == 0 {
-                Ok(program)
-            } else {
-                Err(ParseError::InsufficientActions(action_index))
-            }
-        } else {
-            Err(ParseError::NoInput)
-        }
+        CompleteProgram::parse_with_symbols(input, 2)
     }
 }
 
@@ -102,6 +199,8 @@ impl FromStr for CompleteProgram {
 pub enum ParseError {
     ActionProblem(ActionProblemDetail),
     InsufficientActions(usize),
+    StandardProblem(format::ParseError),
+    NonAscii,
     NoInput,
 }
 
@@ -136,7 +235,7 @@ impl<'a> Iterator for KeyActionIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index < self.program.program.len() {
-            let key: Key = self.index.into();
+            let key = Key::at(self.index, self.program.symbols);
             let lookup = self.program.lookup(&key);
             self.index += 1;
             let action: Option<Action> = lookup.into();
@@ -148,120 +247,94 @@ impl<'a> Iterator for KeyActionIterator<'a> {
 }
 
 /// Iterator for complete progams
+///
+/// The programs are enumerated in the order of the Cartesian product of the actions for each key, where the action for the
+/// first key varies slowest. So program with index `i` has the action with index `d` for key `k`, where `d` is the `k`-th digit of
+/// `i` in base `2kn + 1` for an alphabet of `k` symbols, counting from the most significant digit.
 pub struct CompletePrograms {
-    iterator: Box<dyn Iterator<Item = CompleteProgram>>,
+    n: u8,
+    symbols: u8,
+    actions: Vec<Action>,
+    digits: Option<Vec<usize>>,
+    remaining: Option<u128>,
 }
 
-macro_rules! actions {
-    (1) => {
-        cartesian!(Actions::up_to(1), Actions::up_to(1))
-    };
-    (2) => {
-        cartesian!(
-            Actions::up_to(2),
-            Actions::up_to(2),
-            Actions::up_to(2),
-            Actions::up_to(2)
-        )
-    };
-    (3) => {
-        cartesian!(
-            Actions::up_to(3),
-            Actions::up_to(3),
-            Actions::up_to(3),
-            Actions::up_to(3),
-            Actions::up_to(3),
-            Actions::up_to(3)
-        )
-    };
-    (4) => {
-        cartesian!(
-            Actions::up_to(4),
-            Actions::up_to(4),
-            Actions::up_to(4),
-            Actions::up_to(4),
-            Actions::up_to(4),
-            Actions::up_to(4),
-            Actions::up_to(4),
-            Actions::up_to(4)
-        )
-    };
-    (5) => {
-        cartesian!(
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5),
-            Actions::up_to(5)
-        )
-    };
-}
+impl CompletePrograms {
+    /// Create an iterator that iteratos through all complete programs of a certain number of states
+    pub fn all(n: u8) -> Self {
+        Self::all_with_symbols(n, 2)
+    }
 
-macro_rules! tuple {
-    (1) => {
-        |tuple| vec![tuple.0, tuple.1]
-    };
-    (2) => {
-        |tuple| vec![tuple.0, tuple.1, tuple.2, tuple.3]
-    };
-    (3) => {
-        |tuple| vec![tuple.0, tuple.1, tuple.2, tuple.3, tuple.4, tuple.5]
-    };
-    (4) => {
-        |tuple| {
-            vec![
-                tuple.0, tuple.1, tuple.2, tuple.3, tuple.4, tuple.5, tuple.6, tuple.7,
-            ]
+    /// Create an iterator that iterates through all complete programs of a certain number of states over an alphabet of
+    /// `symbols` symbols.
+    pub fn all_with_symbols(n: u8, symbols: u8) -> Self {
+        Self::starting_at_with_symbols(n, symbols, 0)
+    }
+
+    /// Create an iterator that iterates through all complete programs of a certain number of states, starting at a certain index.
+    pub fn starting_at(n: u8, index: u128) -> Self {
+        Self::starting_at_with_symbols(n, 2, index)
+    }
+
+    /// Create an iterator that iterates through all complete programs of a certain number of states over an alphabet of
+    /// `symbols` symbols, starting at a certain index.
+    pub fn starting_at_with_symbols(n: u8, symbols: u8, index: u128) -> Self {
+        Self::enumerating(n, symbols, index, None)
+    }
+
+    fn enumerating(n: u8, symbols: u8, start: u128, remaining: Option<u128>) -> Self {
+        let actions: Vec<Action> = Actions::with_symbols(n, symbols).collect();
+        let base = actions.len() as u128;
+        let mut digits = vec![0usize; symbols as usize * n as usize];
+        let mut remainder = start;
+        for digit in digits.iter_mut().rev() {
+            *digit = (remainder % base) as usize;
+            remainder /= base;
         }
-    };
-    (5) => {
-        |tuple| {
-            vec![
-                tuple.0, tuple.1, tuple.2, tuple.3, tuple.4, tuple.5, tuple.6, tuple.7, tuple.8,
-                tuple.9,
-            ]
+        Self {
+            n,
+            symbols,
+            actions,
+            digits: if remainder == 0 { Some(digits) } else { None },
+            remaining,
         }
-    };
-}
+    }
 
-macro_rules! all_programs {
-    ($n:tt, $fname:ident) => {
-        fn $fname() -> CompletePrograms {
-            let iterator = actions!($n).map(tuple!($n)).map(|actions| {
-                let mut program = CompleteProgram::new();
-                for (key, action) in Keys::up_to($n).zip(actions) {
-                    program.insert(key, action);
-                }
-                program
-            });
-            CompletePrograms {
-                iterator: Box::new(iterator),
-            }
-        }
-    };
-}
+    /// Create an iterator that iterates through the complete programs of a certain number of states with an index in a range.
+    pub fn range(n: u8, range: Range<u128>) -> Self {
+        Self::range_with_symbols(n, 2, range)
+    }
 
-all_programs!(1, all1);
-all_programs!(2, all2);
-all_programs!(3, all3);
-all_programs!(4, all4);
-all_programs!(5, all5);
+    /// Create an iterator that iterates through the complete programs of a certain number of states over an alphabet of
+    /// `symbols` symbols with an index in a range.
+    pub fn range_with_symbols(n: u8, symbols: u8, range: Range<u128>) -> Self {
+        let remaining = range.end.saturating_sub(range.start);
+        Self::enumerating(n, symbols, range.start, Some(remaining))
+    }
 
-impl CompletePrograms {
-    /// Create an iterator that iteratos through all complete programs of a certain number of states
-    pub fn all(n: u8) -> Self {
-        match n {
-            1 => all1(),
-            2 => all2(),
-            3 => all3(),
-            4 => all4(),
-            5 => all5(),
-            _ => panic!("it is unwise to go beyond 5"),
+    /// The number of complete programs of a certain number of states, if it can be represented.
+    pub fn size(n: u8) -> Option<u128> {
+        Self::size_with_symbols(n, 2)
+    }
+
+    /// The number of complete programs of a certain number of states over an alphabet of `symbols` symbols, if it can be
+    /// represented.
+    pub fn size_with_symbols(n: u8, symbols: u8) -> Option<u128> {
+        let base = 2 * symbols as u128 * n as u128 + 1;
+        base.checked_pow(symbols as u32 * n as u32)
+    }
+
+    fn increment(&mut self) {
+        let base = self.actions.len();
+        if let Some(digits) = &mut self.digits {
+            for digit in digits.iter_mut().rev() {
+                *digit += 1;
+                if *digit < base {
+                    return;
+                }
+                *digit = 0;
+            }
+            self.digits = None;
         }
     }
 }
@@ -270,7 +343,21 @@ impl Iterator for CompletePrograms {
     type Item = CompleteProgram;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iterator.next()
+        if let Some(remaining) = &mut self.remaining {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+        let program = self.digits.as_ref().map(|digits| {
+            let mut program = CompleteProgram::with_symbols(self.symbols);
+            for (key, digit) in Keys::with_symbols(self.n, self.symbols).zip(digits) {
+                program.insert(key, self.actions[*digit]);
+            }
+            program
+        });
+        self.increment();
+        program
     }
 }
 
@@ -283,36 +370,69 @@ mod tests {
     fn programs_can_be_parsed() {
         let mut expected = CompleteProgram::new();
         expected.insert(
-            (State::Number(0), Symbol::Blank),
-            (Symbol::NonBlank, Direction::Right, State::Number(1)),
+            (State::Number(0), Symbol::BLANK),
+            (Symbol::NON_BLANK, Direction::Right, State::Number(1)),
         );
         expected.insert(
-            (State::Number(0), Symbol::NonBlank),
-            (Symbol::Blank, Direction::Right, State::Number(1)),
+            (State::Number(0), Symbol::NON_BLANK),
+            (Symbol::BLANK, Direction::Right, State::Number(1)),
         );
         expected.insert(
-            (State::Number(1), Symbol::Blank),
-            (Symbol::NonBlank, Direction::Left, State::Number(1)),
+            (State::Number(1), Symbol::BLANK),
+            (Symbol::NON_BLANK, Direction::Left, State::Number(1)),
         );
         expected.insert(
-            (State::Number(1), Symbol::NonBlank),
-            (Symbol::NonBlank, Direction::Right, State::Number(2)),
+            (State::Number(1), Symbol::NON_BLANK),
+            (Symbol::NON_BLANK, Direction::Right, State::Number(2)),
         );
 
         assert_eq!(Ok(expected), "1R1 0R1 1L1 1R2".parse())
     }
 
+    #[test]
+    fn programs_can_be_parsed_in_the_standard_format() {
+        let expected: CompleteProgram = "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3 1RH 0L0".parse().unwrap();
+
+        let actual =
+            CompleteProgram::parse_as("1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA", Format::Standard);
+
+        assert_eq!(Ok(expected), actual);
+    }
+
+    #[test]
+    fn undefined_transitions_are_parsed_as_halting() {
+        let expected: CompleteProgram = "1R1   H 1L0 1R1".parse().unwrap();
+
+        let actual = CompleteProgram::parse_as("1RB---_1LA1RB", Format::Standard);
+
+        assert_eq!(Ok(expected), actual);
+    }
+
+    #[test]
+    fn programs_can_be_displayed_in_the_standard_format() {
+        let program: CompleteProgram = "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3   H 0L0".parse().unwrap();
+
+        assert_eq!(
+            "1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA",
+            program.display_as(Format::Standard).to_string()
+        );
+        assert_eq!(
+            "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3   H 0L0",
+            program.display_as(Format::Native).to_string()
+        );
+    }
+
     #[test]
     fn key_action_iterator_can_be_iterated() {
         let program: CompleteProgram = "1R0 0R0".parse().unwrap();
         let expected: Vec<(Key, Action)> = vec![
             (
-                (State::Number(0), Symbol::Blank).into(),
-                (Symbol::NonBlank, Direction::Right, State::Number(0)).into(),
+                (State::Number(0), Symbol::BLANK).into(),
+                (Symbol::NON_BLANK, Direction::Right, State::Number(0)).into(),
             ),
             (
-                (State::Number(0), Symbol::NonBlank).into(),
-                (Symbol::Blank, Direction::Right, State::Number(0)).into(),
+                (State::Number(0), Symbol::NON_BLANK).into(),
+                (Symbol::BLANK, Direction::Right, State::Number(0)).into(),
             ),
         ];
 
@@ -326,12 +446,12 @@ mod tests {
         let program: CompleteProgram = "1R0 0R0".parse().unwrap();
         let expected: Vec<(Key, Action)> = vec![
             (
-                (State::Number(0), Symbol::Blank).into(),
-                (Symbol::NonBlank, Direction::Right, State::Number(0)).into(),
+                (State::Number(0), Symbol::BLANK).into(),
+                (Symbol::NON_BLANK, Direction::Right, State::Number(0)).into(),
             ),
             (
-                (State::Number(0), Symbol::NonBlank).into(),
-                (Symbol::Blank, Direction::Right, State::Number(0)).into(),
+                (State::Number(0), Symbol::NON_BLANK).into(),
+                (Symbol::BLANK, Direction::Right, State::Number(0)).into(),
             ),
         ];
 
@@ -342,15 +462,195 @@ mod tests {
 
     #[test]
     fn all1_contains_correct_number_of_programs() {
-        let number_of_programs = all1().count();
+        let number_of_programs = CompletePrograms::all(1).count();
 
         assert_eq!(number_of_programs, 25);
     }
 
     #[test]
     fn all2_contains_correct_number_of_programs() {
-        let number_of_programs = all2().count();
+        let number_of_programs = CompletePrograms::all(2).count();
 
         assert_eq!(number_of_programs, 6561);
     }
+
+    #[test]
+    fn size_is_known_without_iterating() {
+        assert_eq!(Some(25), CompletePrograms::size(1));
+        assert_eq!(Some(6561), CompletePrograms::size(2));
+        assert_eq!(Some(25u128.pow(12)), CompletePrograms::size(6));
+        assert_eq!(None, CompletePrograms::size(20));
+    }
+
+    #[test]
+    fn programs_are_in_cartesian_order() {
+        let actual: Vec<String> = CompletePrograms::all(1)
+            .skip(4)
+            .take(3)
+            .map(|program| program.to_string())
+            .collect();
+
+        assert_eq!(vec!["  H 1R0", "0L0   H", "0L0 0L0"], actual);
+    }
+
+    #[test]
+    fn programs_can_start_at_an_index() {
+        let expected: Vec<CompleteProgram> = CompletePrograms::all(2).skip(1000).take(10).collect();
+
+        let actual: Vec<CompleteProgram> =
+            CompletePrograms::starting_at(2, 1000).take(10).collect();
+
+        assert_eq!(expected, actual);
+        assert_eq!(0, CompletePrograms::starting_at(2, 6561).count());
+        assert_eq!(1, CompletePrograms::starting_at(2, 6560).count());
+    }
+
+    #[test]
+    fn programs_can_be_ranked_and_unranked() {
+        for (index, program) in CompletePrograms::all(2).enumerate().step_by(97) {
+            assert_eq!(Some(index as u128), program.index());
+            assert_eq!(Some(program), CompleteProgram::from_index(2, index as u128));
+        }
+        assert_eq!(None, CompleteProgram::from_index(2, 6561));
+    }
+
+    #[test]
+    fn indices_that_can_not_be_represented_are_none() {
+        let mut program = CompleteProgram::new();
+        for key in Keys::up_to(20) {
+            program.insert(
+                key,
+                Action::Do {
+                    symbol: Symbol::NON_BLANK,
+                    direction: Direction::Right,
+                    state: State::Number(19),
+                },
+            );
+        }
+
+        assert_eq!(None, program.index());
+    }
+
+    #[test]
+    fn programs_that_write_before_halting_have_no_index() {
+        let halting: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+        let writing: CompleteProgram = "1R1 1L1 1L0 1LH".parse().unwrap();
+
+        assert!(halting.index().is_some());
+        assert_eq!(None, writing.index());
+    }
+
+    #[test]
+    fn programs_can_be_enumerated_by_range() {
+        let actual: Vec<u128> = CompletePrograms::range(4, 1_000_000_000..1_000_000_005)
+            .filter_map(|program| program.index())
+            .collect();
+
+        assert_eq!(
+            vec![
+                1_000_000_000,
+                1_000_000_001,
+                1_000_000_002,
+                1_000_000_003,
+                1_000_000_004
+            ],
+            actual
+        );
+        assert_eq!(2, CompletePrograms::range(1, 23..30).count());
+    }
+
+    #[test]
+    fn programs_over_larger_alphabets_can_be_parsed_and_displayed() {
+        let program = CompleteProgram::parse_with_symbols("1R1 2L1 1RH 2L0 2R1 1L1", 3).unwrap();
+
+        assert_eq!(3, program.symbols());
+        assert_eq!(2, program.states());
+        assert_eq!("1R1 2L1 1RH 2L0 2R1 1L1", program.to_string());
+        assert_eq!(
+            "1RB2LB1RZ_2LA2RB1LB",
+            program.display_as(Format::Standard).to_string()
+        );
+        assert_eq!(
+            Ok(program),
+            CompleteProgram::parse_as("1RB2LB1RZ_2LA2RB1LB", Format::Standard)
+        );
+    }
+
+    #[test]
+    fn symbols_outside_the_alphabet_are_rejected() {
+        assert_eq!(
+            Err(ParseError::ActionProblem(ActionProblemDetail {
+                index: 4,
+                error: action::ParseError::UnknownSymbol("2".to_owned())
+            })),
+            "1R1 2L1 1L0   H".parse::<CompleteProgram>()
+        );
+        assert_eq!(
+            Err(ParseError::InsufficientActions(4)),
+            CompleteProgram::parse_with_symbols("1R1 2L1 1L0   H", 3)
+        );
+    }
+
+    #[test]
+    fn non_ascii_programs_are_rejected() {
+        assert_eq!(
+            Err(ParseError::NonAscii),
+            "1R1 1é1 1L0   H".parse::<CompleteProgram>()
+        );
+        assert_eq!(
+            Err(ParseError::StandardProblem(format::ParseError::NonAscii)),
+            CompleteProgram::parse_as("1RB1LB_1é1RZ", Format::Standard)
+        );
+    }
+
+    #[test]
+    fn parsed_programs_can_be_written_back() {
+        let input = ["1RB1LJ"; 10].join("_");
+        let program = CompleteProgram::parse_as(&input, Format::Standard).unwrap();
+
+        assert_eq!(Ok(program.clone()), program.to_string().parse());
+        assert_eq!(input, program.display_as(Format::Standard).to_string());
+    }
+
+    #[test]
+    fn halting_actions_keep_their_symbol_and_direction() {
+        let input = "1RB0LZ_1LA1RB";
+        let program = CompleteProgram::parse_as(input, Format::Standard).unwrap();
+
+        assert_eq!("1R1 0LH 1L0 1R1", program.to_string());
+        assert_eq!(Ok(program.clone()), program.to_string().parse());
+        assert_eq!(input, program.display_as(Format::Standard).to_string());
+    }
+
+    #[test]
+    fn programs_over_larger_alphabets_can_be_enumerated() {
+        assert_eq!(
+            Some(13u128.pow(6)),
+            CompletePrograms::size_with_symbols(2, 3)
+        );
+        assert_eq!(
+            CompletePrograms::size_with_symbols(1, 4),
+            Some(CompletePrograms::all_with_symbols(1, 4).count() as u128)
+        );
+        for (index, program) in CompletePrograms::range_with_symbols(2, 3, 1000..1100).enumerate() {
+            assert_eq!(Some(1000 + index as u128), program.index());
+            assert_eq!(
+                Some(program),
+                CompleteProgram::from_index_with_symbols(2, 3, 1000 + index as u128)
+            );
+        }
+    }
+
+    #[test]
+    fn programs_with_six_states_can_be_enumerated() {
+        let actual: Vec<String> = CompletePrograms::starting_at(6, 1)
+            .take(1)
+            .map(|program| program.to_string())
+            .collect();
+
+        assert_eq!(
+            vec!["  H   H   H   H   H   H   H   H   H   H   H 0L0"],
+            actual
+        );
+    }
 }
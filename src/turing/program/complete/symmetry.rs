@@ -0,0 +1,390 @@
+//! Programs that are each others mirror image, or that only differ by a relabeling of the non-start states, behave the same.
+//!
+//! Together these symmetries partition the complete programs into equivalence classes. The canonical representative of a class
+//! is the program with the lowest index, see `CompleteProgram::index`.
+use super::{CompleteProgram, CompletePrograms};
+use crate::turing::{
+    direction::Direction,
+    program::{Action, Key, Lookup, Program},
+    state::State,
+};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+impl CompleteProgram {
+    /// The mirror image of this program, i.e. the program where left and right are swapped.
+    pub fn mirrored(&self) -> Self {
+        let program = self
+            .program
+            .iter()
+            .map(|action| match action {
+                Action::Halt => Action::Halt,
+                Action::Do {
+                    symbol,
+                    direction,
+                    state,
+                } => Action::Do {
+                    symbol: *symbol,
+                    direction: match direction {
+                        Direction::Left => Direction::Right,
+                        Direction::Right => Direction::Left,
+                    },
+                    state: *state,
+                },
+            })
+            .collect();
+        Self {
+            symbols: self.symbols,
+            program,
+        }
+    }
+
+    /// Relabel the states of this program. State `s` becomes state `permutation[s]`.
+    pub fn relabeled(&self, permutation: &[u8]) -> Self {
+        let relabel = |state: &State| match state {
+            State::Number(s) => State::Number(permutation[*s as usize]),
+            State::Halted => State::Halted,
+        };
+        let mut program = vec![Action::Halt; self.program.len()];
+        for (index, action) in self.program.iter().enumerate() {
+            let key = Key::at(index, self.symbols);
+            let target: Key = (relabel(&key.state), key.symbol).into();
+            program[target.index(self.symbols)] = match action {
+                Action::Halt => Action::Halt,
+                Action::Do {
+                    symbol,
+                    direction,
+                    state,
+                } => Action::Do {
+                    symbol: *symbol,
+                    direction: *direction,
+                    state: relabel(state),
+                },
+            };
+        }
+        Self {
+            symbols: self.symbols,
+            program,
+        }
+    }
+
+    /// The canonical representative of the equivalence class of this program, together with the size of the class.
+    pub fn canonical(&self) -> (Self, usize) {
+        let mut images = self.images();
+        images.sort_by_key(|image| image.digits());
+        images.dedup();
+        let size = images.len();
+        (images.swap_remove(0), size)
+    }
+
+    /// Determine if this program is the canonical representative of its equivalence class.
+    pub fn is_canonical(&self) -> bool {
+        self.class_size(&Symmetry::all(self.states())).is_some()
+    }
+
+    /// The size of the equivalence class of this program, when it is the canonical representative of that class.
+    ///
+    /// The images are not built. Each image is compared with this program digit by digit, until a digit differs. The images
+    /// equal to this program determine the size of the class, because every image occurs equally often.
+    fn class_size(&self, symmetries: &[Symmetry]) -> Option<usize> {
+        let digits = self.digits();
+        let mut fixed = 0;
+        for symmetry in symmetries {
+            match self.compare_image(symmetry, &digits) {
+                Ordering::Less => return None,
+                Ordering::Equal => fixed += 1,
+                Ordering::Greater => {}
+            }
+        }
+        Some(symmetries.len() / fixed)
+    }
+
+    /// Compare the digits of the image of this program under a symmetry with the digits of this program.
+    fn compare_image(&self, symmetry: &Symmetry, digits: &[usize]) -> Ordering {
+        for (index, digit) in digits.iter().enumerate() {
+            let key = Key::at(index, self.symbols);
+            let source: Key = (symmetry.original(key.state), key.symbol).into();
+            let image = symmetry.apply(&self.program[source.index(self.symbols)]);
+            match self.digit(&image).cmp(digit) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
+        Ordering::Equal
+    }
+
+    fn images(&self) -> Vec<Self> {
+        let mirrored = self.mirrored();
+        permutations(self.states())
+            .iter()
+            .flat_map(|permutation| {
+                vec![self.relabeled(permutation), mirrored.relabeled(permutation)]
+            })
+            .collect()
+    }
+
+    /// The digits of the index of this program.
+    fn digits(&self) -> Vec<usize> {
+        self.program
+            .iter()
+            .map(|action| self.digit(action))
+            .collect()
+    }
+
+    /// The digit of an action in the index of this program. Actions that write and move before they halt have no index, so
+    /// they are ordered after all other actions.
+    fn digit(&self, action: &Action) -> usize {
+        let base = self.program.len() * 2 + 1;
+        match *action {
+            Action::Do {
+                symbol,
+                direction,
+                state: State::Halted,
+            } => Action::from((symbol, direction, State::Number(0)))
+                .index_with_symbols(self.symbols)
+                .map_or(base, |digit| base + digit),
+            _ => action.index_with_symbols(self.symbols).unwrap_or(base),
+        }
+    }
+}
+
+/// A symmetry of programs, i.e. a relabeling of the states, possibly combined with mirroring.
+struct Symmetry {
+    permutation: Vec<u8>,
+    inverse: Vec<u8>,
+    mirror: bool,
+}
+
+impl Symmetry {
+    /// All symmetries of programs with `n` states.
+    fn all(n: u8) -> Vec<Self> {
+        permutations(n)
+            .into_iter()
+            .flat_map(|permutation| {
+                let mut inverse = vec![0; permutation.len()];
+                for (state, label) in permutation.iter().enumerate() {
+                    inverse[*label as usize] = state as u8;
+                }
+                [false, true].map(|mirror| Symmetry {
+                    permutation: permutation.clone(),
+                    inverse: inverse.clone(),
+                    mirror,
+                })
+            })
+            .collect()
+    }
+
+    /// The state that is relabeled as `state`.
+    fn original(&self, state: State) -> State {
+        match state {
+            State::Number(s) => State::Number(self.inverse[s as usize]),
+            State::Halted => State::Halted,
+        }
+    }
+
+    /// The image of an action under this symmetry.
+    fn apply(&self, action: &Action) -> Action {
+        match *action {
+            Action::Halt => Action::Halt,
+            Action::Do {
+                symbol,
+                direction,
+                state,
+            } => Action::Do {
+                symbol,
+                direction: match direction {
+                    Direction::Left if self.mirror => Direction::Right,
+                    Direction::Right if self.mirror => Direction::Left,
+                    direction => direction,
+                },
+                state: match state {
+                    State::Number(s) => State::Number(self.permutation[s as usize]),
+                    State::Halted => State::Halted,
+                },
+            },
+        }
+    }
+}
+
+/// All permutations of the states `0..n` that keep the start state `0` in place.
+fn permutations(n: u8) -> Vec<Vec<u8>> {
+    let mut result = vec![vec![0u8]];
+    for state in 1..n {
+        result = result
+            .into_iter()
+            .flat_map(|permutation| {
+                (1..=permutation.len()).map(move |position| {
+                    let mut extended = permutation.clone();
+                    extended.insert(position, state);
+                    extended
+                })
+            })
+            .collect();
+    }
+    result
+}
+
+/// A program that represents the programs of its equivalence class.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Representative {
+    program: CompleteProgram,
+    multiplicity: usize,
+}
+
+impl Representative {
+    /// The canonical program of the equivalence class
+    pub fn program(&self) -> &CompleteProgram {
+        &self.program
+    }
+}
+
+impl Program for Representative {
+    fn lookup(&self, key: &Key) -> Lookup {
+        self.program.lookup(key)
+    }
+
+    fn multiplicity(&self) -> usize {
+        self.multiplicity
+    }
+
+    fn symbols(&self) -> u8 {
+        self.program.symbols()
+    }
+}
+
+impl Display for Representative {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.program.fmt(formatter)
+    }
+}
+
+/// Iterator over the canonical representatives of the complete programs.
+pub struct Representatives {
+    programs: CompletePrograms,
+    symmetries: Vec<Symmetry>,
+}
+
+impl CompletePrograms {
+    /// Create an iterator that iterates through the canonical representatives of all complete programs of a certain number of
+    /// states. The multiplicity of each representative is the size of its equivalence class.
+    pub fn canonical(n: u8) -> Representatives {
+        Self::canonical_with_symbols(n, 2)
+    }
+
+    /// Create an iterator that iterates through the canonical representatives of all complete programs of a certain number of
+    /// states over an alphabet of `symbols` symbols.
+    pub fn canonical_with_symbols(n: u8, symbols: u8) -> Representatives {
+        Representatives {
+            programs: CompletePrograms::all_with_symbols(n, symbols),
+            symmetries: Symmetry::all(n),
+        }
+    }
+}
+
+impl Iterator for Representatives {
+    type Item = Representative;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let symmetries = &self.symmetries;
+        self.programs.find_map(|program| {
+            program
+                .class_size(symmetries)
+                .map(|multiplicity| Representative {
+                    program,
+                    multiplicity,
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{Assessment, Machine, SimpleTape};
+
+    #[test]
+    fn permutations_keep_the_start_state() {
+        let mut actual = permutations(3);
+        actual.sort();
+
+        assert_eq!(vec![vec![0, 1, 2], vec![0, 2, 1]], actual);
+        assert_eq!(24, permutations(5).len());
+    }
+
+    #[test]
+    fn programs_can_be_mirrored() {
+        let program: CompleteProgram = "1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3   H 0L0".parse().unwrap();
+
+        assert_eq!(
+            "1L1 1R2 1L2 1L1 1L3 0R4 1R0 1R3   H 0R0",
+            program.mirrored().to_string()
+        );
+    }
+
+    #[test]
+    fn programs_can_be_relabeled() {
+        let program: CompleteProgram = "1R1 1L2 1R2 1R1   H 0L0".parse().unwrap();
+
+        assert_eq!(
+            "1R2 1L1   H 0L0 1R1 1R2",
+            program.relabeled(&[0, 2, 1]).to_string()
+        );
+    }
+
+    #[test]
+    fn equivalent_programs_have_the_same_canonical_representative() {
+        let program: CompleteProgram = "1R1 1L2 1R2 1R1   H 0L0".parse().unwrap();
+        let (canonical, size) = program.canonical();
+
+        for image in program.images() {
+            assert_eq!((canonical.clone(), size), image.canonical());
+        }
+        assert_eq!(4, size);
+    }
+
+    #[test]
+    fn representatives_account_for_all_programs() {
+        let total: usize = CompletePrograms::canonical(2)
+            .map(|representative| representative.multiplicity())
+            .sum();
+
+        assert_eq!(6561, total);
+    }
+
+    #[test]
+    fn representatives_are_their_own_canonical_representative() {
+        for representative in CompletePrograms::canonical(3).step_by(997) {
+            let (canonical, size) = representative.program().canonical();
+
+            assert_eq!(representative.program(), &canonical);
+            assert_eq!(representative.multiplicity(), size);
+        }
+    }
+
+    #[test]
+    fn representatives_over_larger_alphabets_account_for_all_programs() {
+        let total: usize = CompletePrograms::canonical_with_symbols(1, 3)
+            .map(|representative| representative.multiplicity())
+            .sum();
+
+        assert_eq!(7usize.pow(3), total);
+    }
+
+    #[test]
+    fn representatives_behave_like_their_class() {
+        let halted = |program: &dyn Program| {
+            let mut machine = Machine::new(SimpleTape::empty(), program);
+            matches!(machine.run(50), Assessment::HaltedIn(_))
+        };
+
+        let halted_representatives: usize = CompletePrograms::canonical(2)
+            .filter(|representative| halted(representative))
+            .map(|representative| representative.multiplicity())
+            .sum();
+        let halted_programs = CompletePrograms::all(2)
+            .filter(|program| halted(program))
+            .count();
+
+        assert_eq!(halted_programs, halted_representatives);
+    }
+}
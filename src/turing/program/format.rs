@@ -0,0 +1,215 @@
+//! Textual formats in which programs can be written down.
+//!
+//! Besides the native format of this project, e.g. `1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3 1RH 0L0`, programs can be written in the
+//! [standard format](https://discuss.bbchallenge.org/t/standard-tm-text-format/60) that the wider community uses, e.g.
+//! `1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA`. In the standard format states are letters, the transitions of consecutive states are
+//! separated by an `_`, undefined transitions are written as `---` and the halt state is written as `Z`. A halting transition
+//! writes and moves before it halts, so `0LZ` reads as the native `0LH`.
+use super::{Action, Key, Keys, Lookup, Program};
+use crate::turing::{direction::Direction, state::State, symbol::Symbol};
+use std::fmt::{self, Display, Formatter};
+
+/// The various formats a program can be written in.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Format {
+    /// The format of this project, e.g. `1R1 1L2 1R2 1R1 1R3 0L4 1L0 1L3 1RH 0L0`.
+    Native,
+    /// The standard format of the community, e.g. `1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA`.
+    Standard,
+}
+
+/// A program that displays itself in a certain format.
+pub struct Formatted<'a, P> {
+    pub(crate) program: &'a P,
+    pub(crate) format: Format,
+}
+
+/// Write the program with `n` states in the standard format.
+pub(crate) fn write_standard(
+    formatter: &mut Formatter,
+    program: &dyn Program,
+    n: u8,
+) -> fmt::Result {
+    let symbols = program.symbols();
+    let transitions: Vec<String> = Keys::with_symbols(n, symbols)
+        .map(|key| program.lookup(&key))
+        .map(|lookup| match lookup {
+            Lookup::Determined(action) => standard_action(&action),
+            _ => "---".to_string(),
+        })
+        .collect();
+    let states: Vec<String> = transitions
+        .chunks(symbols as usize)
+        .map(|chunk| chunk.concat())
+        .collect();
+    formatter.write_str(&states.join("_"))
+}
+
+/// An `Action::Halt` does not write or move, but the standard format has no such action. It is written as `1RZ`, like the
+/// halting transitions of the community. Other halting actions keep their symbol and direction.
+fn standard_action(action: &Action) -> String {
+    match action {
+        Action::Halt => "1RZ".to_string(),
+        Action::Do {
+            symbol,
+            direction,
+            state,
+        } => format!("{}{}{}", symbol, direction, StandardState(*state)),
+    }
+}
+
+struct StandardState(State);
+
+impl Display for StandardState {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.0 {
+            State::Halted => write!(f, "Z"),
+            State::Number(n) => write!(f, "{}", (b'A' + n) as char),
+        }
+    }
+}
+
+/// Parse a program in the standard format into the number of symbols and its keys with, possibly undefined, actions.
+///
+/// The number of symbols follows from the number of transitions of the first state.
+pub(crate) fn parse_standard(input: &str) -> Result<Transitions, ParseError> {
+    if input.is_empty() {
+        return Err(ParseError::NoInput);
+    }
+    if !input.is_ascii() {
+        return Err(ParseError::NonAscii);
+    }
+    let width = input.split('_').next().map(str::len).unwrap_or_default();
+    if width < 6 || !width.is_multiple_of(3) || width > 30 {
+        return Err(ParseError::IncorrectLength(0, width));
+    }
+    let symbols = (width / 3) as u8;
+    let states = input.split('_').count();
+    if states > MAXIMUM_STATES as usize {
+        return Err(ParseError::TooManyStates(states));
+    }
+    let mut transitions = Vec::new();
+    for (index, group) in input.split('_').enumerate() {
+        if group.len() != width {
+            return Err(ParseError::IncorrectLength(index, group.len()));
+        }
+        for offset in 0..symbols as usize {
+            let action = parse_standard_action(&group[3 * offset..3 * offset + 3], symbols)?;
+            let key = Key::at(symbols as usize * index + offset, symbols);
+            transitions.push((key, action));
+        }
+    }
+    Ok((symbols, transitions))
+}
+
+/// The maximum number of states of a program that is parsed.
+///
+/// The native format writes a state as a single digit, so a program with more states could not be written back.
+const MAXIMUM_STATES: u8 = 10;
+
+/// The number of symbols of a program, together with its keys and, possibly undefined, actions.
+pub(crate) type Transitions = (u8, Vec<(Key, Option<Action>)>);
+
+fn parse_standard_action(input: &str, symbols: u8) -> Result<Option<Action>, ParseError> {
+    if input == "---" {
+        return Ok(None);
+    }
+    let symbol = input[0..1]
+        .parse::<Symbol>()
+        .ok()
+        .filter(|symbol| symbol.digit() < symbols)
+        .ok_or_else(|| ParseError::UnknownSymbol(input[0..1].to_owned()))?;
+    let direction = input[1..2]
+        .parse::<Direction>()
+        .map_err(|_| ParseError::UnknownDirection(input[1..2].to_owned()))?;
+    let state = match input.as_bytes()[2] {
+        b'Z' => State::Halted,
+        letter @ b'A'..=b'J' => State::Number(letter - b'A'),
+        _ => return Err(ParseError::UnknownState(input[2..3].to_owned())),
+    };
+    Ok(Some(Action::Do {
+        symbol,
+        direction,
+        state,
+    }))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    IncorrectLength(usize, usize),
+    UnknownSymbol(String),
+    UnknownDirection(String),
+    UnknownState(String),
+    TooManyStates(usize),
+    NonAscii,
+    NoInput,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_actions_can_be_parsed() {
+        assert_eq!(Ok(None), parse_standard_action("---", 2));
+        assert_eq!(
+            Ok(Some(Action::Do {
+                symbol: Symbol::NON_BLANK,
+                direction: Direction::Right,
+                state: State::Halted
+            })),
+            parse_standard_action("1RZ", 2)
+        );
+        assert_eq!(
+            Ok(Some(Action::Do {
+                symbol: Symbol::BLANK,
+                direction: Direction::Left,
+                state: State::Number(2)
+            })),
+            parse_standard_action("0LC", 2)
+        );
+        assert_eq!(
+            Ok(Some(Action::Do {
+                symbol: Symbol::new(2),
+                direction: Direction::Right,
+                state: State::Number(1)
+            })),
+            parse_standard_action("2RB", 3)
+        );
+    }
+
+    #[test]
+    fn parse_standard_checks_for_errors() {
+        assert_eq!(Err(ParseError::NoInput), parse_standard(""));
+        assert_eq!(
+            Err(ParseError::IncorrectLength(1, 5)),
+            parse_standard("1RB1LB_1LA1R")
+        );
+        assert_eq!(
+            Err(ParseError::UnknownSymbol("2".to_owned())),
+            parse_standard("2RB1LB")
+        );
+        assert_eq!(
+            Err(ParseError::UnknownDirection("S".to_owned())),
+            parse_standard("1SB1LB")
+        );
+        assert_eq!(
+            Err(ParseError::UnknownState("a".to_owned())),
+            parse_standard("1Ra1LB")
+        );
+        assert_eq!(
+            Err(ParseError::UnknownState("K".to_owned())),
+            parse_standard("1RK1LB")
+        );
+        assert_eq!(
+            Err(ParseError::TooManyStates(11)),
+            parse_standard(&["1RA1LA"; 11].join("_"))
+        );
+    }
+
+    #[test]
+    fn non_ascii_input_is_rejected() {
+        assert_eq!(Err(ParseError::NonAscii), parse_standard("1é1LB"));
+        assert_eq!(Err(ParseError::NonAscii), parse_standard("1RB1LB_1LA1Ré"));
+    }
+}
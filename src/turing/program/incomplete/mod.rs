@@ -1,20 +1,32 @@
 //! Incomplete programs
-use super::{Key, Keys, Lookup, Program};
+use super::{
+    action,
+    format::{self, Format, Formatted},
+    Key, Keys, Lookup, Program,
+};
 use crate::turing::{Action, Actions, State};
-use std::cmp::{max, min};
 use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
 /// An incomplete program
+///
+/// Besides the programs that can be obtained by completing its undetermined actions, an incomplete program can represent
+/// programs that only differ by a relabeling of states. The weight of an incomplete program tracks how many relabelings it
+/// represents.
 #[derive(Debug, PartialEq, Eq)]
 pub struct IncompleteProgram {
     n: u8,
+    symbols: u8,
+    weight: usize,
     program: Vec<Option<Action>>,
 }
 
 impl Program for IncompleteProgram {
     fn lookup(&self, key: &Key) -> Lookup {
-        let idx = key.idx();
-        match self.program.get(idx) {
+        if key.symbol.digit() >= self.symbols {
+            return Lookup::Unknown;
+        }
+        match self.program.get(key.index(self.symbols)) {
             Some(Some(action)) => Lookup::Determined(*action),
             Some(None) => Lookup::Indeterminate,
             None => Lookup::Unknown,
@@ -22,23 +34,47 @@ impl Program for IncompleteProgram {
     }
 
     fn multiplicity(&self) -> usize {
-        self.program
-            .iter()
-            .filter(|action| action.is_none())
-            .map(|_| (4 * self.n + 1) as usize)
-            .product()
+        self.weight
+            * self
+                .program
+                .iter()
+                .filter(|action| action.is_none())
+                .map(|_| 2 * self.symbols as usize * self.n as usize + 1)
+                .product::<usize>()
+    }
+
+    fn symbols(&self) -> u8 {
+        self.symbols
     }
 }
 
 impl IncompleteProgram {
     /// Create an incomplete program with a maximum number of states.
     pub fn with_states(n: u8) -> Self {
+        Self::with_states_and_symbols(n, 2)
+    }
+
+    /// Create an incomplete program with a maximum number of states, over an alphabet of `symbols` symbols.
+    pub fn with_states_and_symbols(n: u8, symbols: u8) -> Self {
         Self {
             n,
-            program: vec![None; 2 * n as usize],
+            symbols,
+            weight: 1,
+            program: vec![None; symbols as usize * n as usize],
         }
     }
 
+    /// The number of states of this program.
+    pub fn states(&self) -> u8 {
+        self.n
+    }
+
+    /// Multiply the weight of this program by a factor, e.g. to account for programs that are omitted because they behave the same.
+    pub fn weighted(mut self, factor: usize) -> Self {
+        self.weight *= factor;
+        self
+    }
+
     /// Insert an action for a certain key
     pub fn insert<K, A>(&mut self, key: K, action: A)
     where
@@ -46,26 +82,102 @@ impl IncompleteProgram {
         A: Into<Action>,
     {
         let key = key.into();
-        self.program[key.idx()] = Some(action.into());
+        self.program[key.index(self.symbols)] = Some(action.into());
     }
 
     /// return an iterator that extends this program in all sensible ways.
+    ///
+    /// States that do not occur in the program are interchangeable. So only the first of these states is used as a target and
+    /// the extension is weighted with the number of states it represents.
     pub fn extentions<K>(&self, key: K) -> Extentions
     where
         K: Into<Key>,
     {
         Extentions::of(self.clone(), key.into())
     }
+
+    /// Parse a program written in a certain format.
+    ///
+    /// Undefined transitions, i.e. `???` in the native format and `---` in the standard format, are left indeterminate.
+    pub fn parse_as(input: &str, format: Format) -> Result<Self, ParseError> {
+        match format {
+            Format::Native => input.parse(),
+            Format::Standard => {
+                let (symbols, transitions) =
+                    format::parse_standard(input).map_err(ParseError::StandardProblem)?;
+                let mut program = IncompleteProgram::with_states_and_symbols(
+                    (transitions.len() / symbols as usize) as u8,
+                    symbols,
+                );
+                for (key, action) in transitions {
+                    if let Some(action) = action {
+                        program.insert(key, action);
+                    }
+                }
+                Ok(program)
+            }
+        }
+    }
+
+    /// Parse a program in the native format over an alphabet of `symbols` symbols.
+    ///
+    /// Parsing a program with `str::parse` assumes an alphabet of two symbols.
+    pub fn parse_with_symbols(input: &str, symbols: u8) -> Result<Self, ParseError> {
+        if input.is_empty() {
+            return Err(ParseError::NoInput);
+        }
+        if !input.is_ascii() {
+            return Err(ParseError::NonAscii);
+        }
+        let number_of_actions = (input.len() + 1) / 4;
+        if !number_of_actions.is_multiple_of(symbols as usize) {
+            return Err(ParseError::InsufficientActions(number_of_actions));
+        }
+        let mut program = IncompleteProgram::with_states_and_symbols(
+            (number_of_actions / symbols as usize) as u8,
+            symbols,
+        );
+        for action_index in 0..number_of_actions {
+            let index = 4 * action_index;
+            let description = &input[index..(index + 3)];
+            if description != "???" {
+                let action = description
+                    .parse::<Action>()
+                    .and_then(|action| match action {
+                        Action::Do { symbol, .. } if symbol.digit() >= symbols => {
+                            Err(action::ParseError::UnknownSymbol(symbol.to_string()))
+                        }
+                        action => Ok(action),
+                    })
+                    .map_err(|error| {
+                        ParseError::ActionProblem(ActionProblemDetail { index, error })
+                    })?;
+                program.insert(Key::at(action_index, symbols), action);
+            }
+        }
+        Ok(program)
+    }
+
+    /// Display this program in a certain format.
+    pub fn display_as(&self, format: Format) -> Formatted<'_, Self> {
+        Formatted {
+            program: self,
+            format,
+        }
+    }
 }
 
 impl Clone for IncompleteProgram {
     fn clone(&self) -> Self {
-        let mut program = IncompleteProgram::with_states(self.n);
+        let mut program =
+            IncompleteProgram::with_states_and_symbols(self.n, self.symbols).weighted(self.weight);
         self.program
             .iter()
             .enumerate()
             .filter(|(_, action)| action.is_some())
-            .for_each(|(index, action)| program.insert(index, action.unwrap()));
+            .for_each(|(index, action)| {
+                program.insert(Key::at(index, self.symbols), action.unwrap())
+            });
 
         program
     }
@@ -73,7 +185,7 @@ impl Clone for IncompleteProgram {
 
 impl Display for IncompleteProgram {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
-        let actions: Vec<String> = Keys::up_to(self.n)
+        let actions: Vec<String> = Keys::with_symbols(self.n, self.symbols)
             .map(|k| self.lookup(&k))
             .map(|l| {
                 let ao: Option<Action> = l.into();
@@ -85,33 +197,77 @@ impl Display for IncompleteProgram {
     }
 }
 
+impl<'a> Display for Formatted<'a, IncompleteProgram> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self.format {
+            Format::Native => self.program.fmt(formatter),
+            Format::Standard => format::write_standard(formatter, self.program, self.program.n),
+        }
+    }
+}
+
+impl FromStr for IncompleteProgram {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        IncompleteProgram::parse_with_symbols(input, 2)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    ActionProblem(ActionProblemDetail),
+    InsufficientActions(usize),
+    StandardProblem(format::ParseError),
+    NonAscii,
+    NoInput,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ActionProblemDetail {
+    index: usize,
+    error: action::ParseError,
+}
+
 pub struct Extentions {
     key: Key,
     program: IncompleteProgram,
-    iterator: Box<dyn Iterator<Item = Action>>,
+    iterator: Box<dyn Iterator<Item = (Action, usize)>>,
 }
 
 impl Extentions {
     fn of(program: IncompleteProgram, key: Key) -> Self {
-        let k: u8 = match key.state {
-            State::Number(s) => s,
-            State::Halted => 0, // Does not occur
-        };
-        let seen_state = program
-            .program
-            .iter()
-            .enumerate()
-            .filter(|(_, action)| action.is_some())
-            .map(|(index, _)| index.into())
-            .map(|state| match state {
-                State::Number(s) => s,
-                State::Halted => 0, // Does not occur
-            })
-            .max()
-            .map(|s| max(s, k))
-            .unwrap_or(k);
-        let states_to_explore = min(program.n - 1, seen_state + 1);
-        let iterator = Actions::up_to(states_to_explore + 1);
+        let mut seen = vec![false; program.n as usize];
+        seen[0] = true;
+        if let State::Number(s) = key.state {
+            seen[s as usize] = true;
+        }
+        for action in program.program.iter().flatten() {
+            if let Action::Do {
+                state: State::Number(s),
+                ..
+            } = action
+            {
+                seen[*s as usize] = true;
+            }
+        }
+        let unseen = seen.iter().filter(|s| !**s).count();
+        let fresh = seen.iter().position(|s| !*s).map(|s| s as u8);
+        let iterator = Actions::with_symbols(program.n, program.symbols).filter_map(
+            move |action| match action {
+                Action::Do {
+                    state: State::Number(s),
+                    ..
+                } if !seen[s as usize] => {
+                    if Some(s) == fresh {
+                        Some((action, unseen))
+                    } else {
+                        None
+                    }
+                }
+                _ => Some((action, 1)),
+            },
+        );
         Self {
             key,
             program,
@@ -124,8 +280,9 @@ impl Iterator for Extentions {
     type Item = IncompleteProgram;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut program = self.program.clone();
-        self.iterator.next().map(move |action| {
+        let program = self.program.clone();
+        self.iterator.next().map(move |(action, weight)| {
+            let mut program = program.weighted(weight);
             program.insert(self.key, action);
             program
         })
@@ -141,8 +298,8 @@ mod tests {
     fn incomplete_programs_can_be_displayed() {
         let mut program = IncompleteProgram::with_states(1);
         program.insert(
-            (State::Number(0), Symbol::Blank),
-            (Symbol::NonBlank, Direction::Right, State::Number(0)),
+            (State::Number(0), Symbol::BLANK),
+            (Symbol::NON_BLANK, Direction::Right, State::Number(0)),
         );
 
         let actual = format!("{}", program);
@@ -150,12 +307,47 @@ mod tests {
         assert_eq!("1R0 ???", actual);
     }
 
+    #[test]
+    fn incomplete_programs_can_be_parsed() {
+        let mut expected = IncompleteProgram::with_states(2);
+        expected.insert(
+            (State::Number(0), Symbol::BLANK),
+            (Symbol::NON_BLANK, Direction::Right, State::Number(1)),
+        );
+        expected.insert((State::Number(1), Symbol::NON_BLANK), Action::Halt);
+
+        assert_eq!(Ok(expected), "1R1 ??? ???   H".parse());
+    }
+
+    #[test]
+    fn non_ascii_incomplete_programs_are_rejected() {
+        assert_eq!(
+            Err(ParseError::NonAscii),
+            "1R1 ??? ??é   H".parse::<IncompleteProgram>()
+        );
+        assert_eq!(
+            Err(ParseError::StandardProblem(format::ParseError::NonAscii)),
+            IncompleteProgram::parse_as("1RB---_---1Ré", Format::Standard)
+        );
+    }
+
+    #[test]
+    fn incomplete_programs_round_trip_the_standard_format() {
+        let program = IncompleteProgram::parse_as("1RB---_---1RZ", Format::Standard).unwrap();
+
+        assert_eq!("1R1 ??? ??? 1RH", program.to_string());
+        assert_eq!(
+            "1RB---_---1RZ",
+            program.display_as(Format::Standard).to_string()
+        );
+    }
+
     #[test]
     fn empty_incomplete_programs_can_be_extended() {
         let program = IncompleteProgram::with_states(2);
 
         let actual: Vec<String> = program
-            .extentions((State::Number(0), Symbol::Blank))
+            .extentions((State::Number(0), Symbol::BLANK))
             .map(|p| format!("{}", p))
             .collect();
 
@@ -174,4 +366,47 @@ mod tests {
             actual,
         )
     }
+
+    #[test]
+    fn extentions_only_target_a_single_unseen_state() {
+        let mut program = IncompleteProgram::with_states(4);
+        program.insert(
+            (State::Number(0), Symbol::BLANK),
+            (Symbol::NON_BLANK, Direction::Right, State::Number(1)),
+        );
+
+        let actual: Vec<(String, usize)> = program
+            .extentions((State::Number(1), Symbol::BLANK))
+            .map(|p| (format!("{}", p), p.multiplicity()))
+            .collect();
+
+        assert_eq!(13, actual.len());
+        assert_eq!(
+            (
+                "1R1 ??? 1R1 ??? ??? ??? ??? ???".to_string(),
+                17usize.pow(6)
+            ),
+            actual[8]
+        );
+        assert_eq!(
+            (
+                "1R1 ??? 0L2 ??? ??? ??? ??? ???".to_string(),
+                2 * 17usize.pow(6)
+            ),
+            actual[9]
+        );
+    }
+
+    #[test]
+    fn multiplicities_account_for_the_number_of_symbols() {
+        let program = IncompleteProgram::parse_with_symbols("1R1 ??? ??? ??? ??? ???", 3).unwrap();
+
+        assert_eq!(13usize.pow(5), program.multiplicity());
+        assert_eq!(
+            13,
+            program
+                .extentions((State::Number(0), Symbol::new(1)))
+                .count()
+        );
+    }
 }
@@ -1,7 +1,6 @@
 /// The configuration a Turing machine is in provides a key to lookup in a progam
 use crate::turing::state::{State, States};
 use crate::turing::symbol::{Symbol, Symbols};
-use cartesian::*;
 
 /// The current configuration of a Turing machine
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -23,20 +22,33 @@ impl From<(State, Symbol)> for Key {
 
 impl From<usize> for Key {
     fn from(index: usize) -> Self {
-        let state: State = index.into();
-        let symbol: Symbol = index.into();
-        Self { state, symbol }
+        Key::at(index, 2)
     }
 }
 
 impl Key {
+    /// The key at a certain index of a program with an alphabet of `symbols` symbols, see `Key::index`.
+    pub fn at(index: usize, symbols: u8) -> Self {
+        let symbols = symbols as usize;
+        Self {
+            state: State::Number((index / symbols) as u8),
+            symbol: Symbol::new((index % symbols) as u8),
+        }
+    }
+
     /// Keys have an index.
-    /// TODO describe why and how it is calculated
+    ///
+    /// Programs store their actions in the order of `Keys::up_to`. So the index of a key is the position of its action in an
+    /// alphabet of two symbols.
     pub fn idx(&self) -> usize {
-        match (self.state, self.symbol) {
-            (State::Number(s), Symbol::Blank) => (2 * s) as usize,
-            (State::Number(s), Symbol::NonBlank) => (2 * s + 1) as usize,
-            _ => 0,
+        self.index(2)
+    }
+
+    /// The position of the action for this key in a program with an alphabet of `symbols` symbols.
+    pub fn index(&self, symbols: u8) -> usize {
+        match self.state {
+            State::Number(s) => s as usize * symbols as usize + self.symbol.digit() as usize,
+            State::Halted => 0,
         }
     }
 }
@@ -49,8 +61,13 @@ pub struct Keys {
 impl Keys {
     /// Iterate through a number of keys up to a maximum
     pub fn up_to(maximum: u8) -> Self {
-        let iterator = cartesian!(States::non_halted_up_to(maximum), Symbols::all()).map(|tuple| {
-            tuple.into()
+        Self::with_symbols(maximum, 2)
+    }
+
+    /// Iterate through a number of keys up to a maximum, for an alphabet of `symbols` symbols.
+    pub fn with_symbols(maximum: u8, symbols: u8) -> Self {
+        let iterator = States::non_halted_up_to(maximum).flat_map(move |state| {
+            Symbols::up_to(symbols).map(move |symbol| (state, symbol).into())
         });
         Self {
             iterator: Box::new(iterator),
@@ -74,12 +91,22 @@ mod tests {
     fn keys_up_to_contain_all_keys_up_to_maximum() {
         let actual: Vec<Key> = Keys::up_to(2).collect();
         let expected: Vec<Key> = vec![
-            (State::Number(0), Symbol::Blank).into(),
-            (State::Number(0), Symbol::NonBlank).into(),
-            (State::Number(1), Symbol::Blank).into(),
-            (State::Number(1), Symbol::NonBlank).into(),
+            (State::Number(0), Symbol::BLANK).into(),
+            (State::Number(0), Symbol::NON_BLANK).into(),
+            (State::Number(1), Symbol::BLANK).into(),
+            (State::Number(1), Symbol::NON_BLANK).into(),
         ];
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn keys_know_their_index() {
+        for symbols in 2..=4 {
+            for (index, key) in Keys::with_symbols(3, symbols).enumerate() {
+                assert_eq!(index, key.index(symbols));
+                assert_eq!(key, Key::at(index, symbols));
+            }
+        }
+    }
 }
@@ -2,11 +2,13 @@
 
 mod action;
 mod complete;
+mod format;
 mod incomplete;
 mod key;
 
 pub use action::{Action, Actions};
-pub use complete::{CompleteProgram, CompletePrograms};
+pub use complete::{CompleteProgram, CompletePrograms, Representative, Representatives};
+pub use format::{Format, Formatted};
 pub use incomplete::IncompleteProgram;
 pub use key::{Key, Keys};
 use std::convert::From;
@@ -20,6 +22,11 @@ pub trait Program {
     fn multiplicity(&self) -> usize {
         1
     }
+
+    /// The number of symbols in the alphabet of this program.
+    fn symbols(&self) -> u8 {
+        2
+    }
 }
 
 /// The result of looking up a certain key in a program.
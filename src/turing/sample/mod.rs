@@ -0,0 +1,143 @@
+//! Sample programs at random.
+//!
+//! For larger number of states it is not feasible to run all programs. Instead one can estimate statistics by running a
+//! random sample of programs. A `Sampler` is seeded, so that a sample can be reproduced.
+//!
+//! ```
+//! # use busy_beaver::{report::Report, turing::{Machine, Sampler, SimpleTape}};
+//! let mut sampler = Sampler::new(5, 37);
+//! let mut report = Report::sampling();
+//! for _ in 0..100 {
+//!     let program = sampler.program();
+//!     let mut machine = Machine::new(SimpleTape::empty(), &program);
+//!     report.update_with(&machine.run(1_000));
+//! }
+//! println!("{:?}", report.estimates());
+//! ```
+use super::{
+    machine::Assessment,
+    program::{Action, Actions, CompleteProgram, IncompleteProgram, Keys, Program},
+    tape::Tape,
+    tree::{roots, Candidate, Exploration},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Draws programs at random.
+pub struct Sampler {
+    n: u8,
+    symbols: u8,
+    actions: Vec<Action>,
+    rng: StdRng,
+}
+
+impl Sampler {
+    /// Create a sampler for programs with `n` states, seeded with `seed`.
+    pub fn new(n: u8, seed: u64) -> Self {
+        Self::with_symbols(n, 2, seed)
+    }
+
+    /// Create a sampler for programs with `n` states over an alphabet of `symbols` symbols, seeded with `seed`.
+    pub fn with_symbols(n: u8, symbols: u8, seed: u64) -> Self {
+        Self {
+            n,
+            symbols,
+            actions: Actions::with_symbols(n, symbols).collect(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Draw a complete program uniformly from all complete programs.
+    pub fn program(&mut self) -> CompleteProgram {
+        let mut program = CompleteProgram::with_symbols(self.symbols);
+        for key in Keys::with_symbols(self.n, self.symbols) {
+            let index = self.rng.gen_range(0..self.actions.len());
+            program.insert(key, self.actions[index]);
+        }
+        program
+    }
+
+    /// Draw a leaf of the tree normal form, see `TreeNormalForm`, with a probability proportional to its multiplicity.
+    ///
+    /// Each machine runs for at most `maximum` steps. Since leafs with a large multiplicity are drawn more often, each leaf
+    /// should count only once when estimating statistics.
+    pub fn leaf<T>(&mut self, maximum: u128) -> (IncompleteProgram, Assessment)
+    where
+        T: Tape + Clone + Default,
+    {
+        let mut candidates = roots::<T>(self.n, self.symbols);
+        loop {
+            let candidate = self.choose(candidates);
+            match candidate.explore(maximum) {
+                Exploration::Leaf(program, assessment) => return (program, *assessment),
+                Exploration::Branch(extensions) => candidates = extensions,
+            }
+        }
+    }
+
+    fn choose<T>(&mut self, mut candidates: Vec<Candidate<T>>) -> Candidate<T> {
+        let total: usize = candidates
+            .iter()
+            .map(|candidate| candidate.program.multiplicity())
+            .sum();
+        let mut pick = self.rng.gen_range(0..total);
+        let index = candidates
+            .iter()
+            .position(|candidate| {
+                let multiplicity = candidate.program.multiplicity();
+                if pick < multiplicity {
+                    true
+                } else {
+                    pick -= multiplicity;
+                    false
+                }
+            })
+            .unwrap(/* safe because pick is less than the total */);
+        candidates.swap_remove(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::SimpleTape;
+
+    #[test]
+    fn samplers_with_the_same_seed_draw_the_same_programs() {
+        let mut sampler = Sampler::new(4, 37);
+        let mut other = Sampler::new(4, 37);
+
+        for _ in 0..10 {
+            assert_eq!(sampler.program(), other.program());
+        }
+    }
+
+    #[test]
+    fn sampled_programs_are_complete() {
+        let mut sampler = Sampler::new(3, 37);
+
+        let program = sampler.program();
+
+        assert_eq!(6, program.into_iter().count());
+
+        let mut sampler = Sampler::with_symbols(2, 4, 37);
+
+        let program = sampler.program();
+
+        assert_eq!(8, program.into_iter().count());
+        assert_eq!(4, program.symbols());
+    }
+
+    #[test]
+    fn sampled_leafs_are_leafs_of_the_tree() {
+        let mut sampler = Sampler::new(2, 37);
+
+        for _ in 0..10 {
+            let (program, assessment) = sampler.leaf::<SimpleTape>(100);
+            let details = match assessment {
+                Assessment::HaltedIn(details) | Assessment::NotHalted(details) => details,
+                _ => panic!("leafs either halt or run out of steps"),
+            };
+            assert_eq!(program.multiplicity(), details.multiplicity);
+        }
+    }
+}
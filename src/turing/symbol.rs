@@ -1,46 +1,55 @@
 //! The alphabet the Turing machin can write on the tape.
 //!
-//! We are only interested in an alphabet of size two.
+//! An alphabet of `k` symbols consists of the digits `0` up to `k - 1`, where `0` is the blank symbol. Most of the time we are
+//! interested in an alphabet of size two, but the number of symbols is a parameter of a program.
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
-/// The various symbols that can be written on the tape.
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
-pub enum Symbol {
+/// The various symbols that can be written on the tape, represented by their digit.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Default)]
+pub struct Symbol(u8);
+
+impl Symbol {
     /// the blank symbol, represented as "0"
-    Blank,
-    /// the non blank symbol, represented as "1"
-    NonBlank,
-}
+    pub const BLANK: Symbol = Symbol(0);
+    /// the first non blank symbol, represented as "1". The only non blank symbol of an alphabet of size two.
+    pub const NON_BLANK: Symbol = Symbol(1);
+
+    /// The symbol represented by a digit.
+    ///
+    /// A symbol does not know the alphabet it belongs to. Programs report a symbol outside of their alphabet as unknown.
+    pub fn new(digit: u8) -> Self {
+        Symbol(digit)
+    }
 
-impl Default for Symbol {
-    fn default() -> Self {
-        Symbol::Blank
+    /// The digit that represents this symbol, which is also its position in the alphabet.
+    pub fn digit(&self) -> u8 {
+        self.0
+    }
+
+    /// Determine if this symbol is the blank symbol.
+    pub fn is_blank(&self) -> bool {
+        *self == Symbol::BLANK
     }
 }
 
 impl Default for &Symbol {
     fn default() -> Self {
-        &Symbol::Blank
+        &Symbol::BLANK
     }
 }
 
 impl Display for Symbol {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self {
-            Symbol::Blank => write!(f, "0"),
-            Symbol::NonBlank => write!(f, "1"),
-        }
+        write!(f, "{}", self.0)
     }
 }
 
+/// The symbol at a certain index of an alphabet of size two.
 impl From<usize> for Symbol {
     fn from(index: usize) -> Self {
-        match index % 2 {
-            1 => Symbol::NonBlank,
-            _ => Symbol::Blank,
-        }
+        Symbol((index % 2) as u8)
     }
 }
 
@@ -48,9 +57,8 @@ impl FromStr for Symbol {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        match input {
-            "0" => Ok(Symbol::Blank),
-            "1" => Ok(Symbol::NonBlank),
+        match input.as_bytes() {
+            [digit @ b'0'..=b'9'] => Ok(Symbol(digit - b'0')),
             _ => Err(ParseError::UnknownSymbol(input.to_owned())),
         }
     }
@@ -63,14 +71,21 @@ pub enum ParseError {
 
 /// Iterator for `Symbol`
 pub struct Symbols {
-    current: Option<Symbol>,
+    symbols: u8,
+    current: u8,
 }
 
 impl Symbols {
-    /// Create an iterator for all symbols.
+    /// Create an iterator for all symbols of an alphabet of size two.
     pub fn all() -> Self {
+        Self::up_to(2)
+    }
+
+    /// Create an iterator for the symbols of an alphabet of a certain size.
+    pub fn up_to(symbols: u8) -> Self {
         Self {
-            current: Some(Symbol::Blank),
+            symbols,
+            current: 0,
         }
     }
 }
@@ -79,12 +94,12 @@ impl Iterator for Symbols {
     type Item = Symbol;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let item = self.current;
-        self.current = match item {
-            Some(Symbol::Blank) => Some(Symbol::NonBlank),
-            _ => None,
-        };
-        item
+        if self.current < self.symbols {
+            self.current += 1;
+            Some(Symbol(self.current - 1))
+        } else {
+            None
+        }
     }
 }
 
@@ -94,26 +109,45 @@ mod tests {
 
     #[test]
     fn same_symbols_are_equal() {
-        assert_eq!(Symbol::Blank, Symbol::Blank);
-        assert_eq!(Symbol::NonBlank, Symbol::NonBlank);
+        assert_eq!(Symbol::BLANK, Symbol::BLANK);
+        assert_eq!(Symbol::NON_BLANK, Symbol::NON_BLANK);
     }
 
     #[test]
     fn distinct_symbols_are_non_equal() {
-        assert_ne!(Symbol::Blank, Symbol::NonBlank);
-        assert_ne!(Symbol::NonBlank, Symbol::Blank);
+        assert_ne!(Symbol::BLANK, Symbol::NON_BLANK);
+        assert_ne!(Symbol::NON_BLANK, Symbol::BLANK);
+    }
+
+    #[test]
+    fn symbols_can_be_created_from_an_index() {
+        assert_eq!(Symbol::BLANK, Symbol::from(0));
+        assert_eq!(Symbol::NON_BLANK, Symbol::from(1));
+        assert_eq!(Symbol::BLANK, Symbol::from(2));
     }
 
     #[test]
     fn symbols_can_be_parsed() {
-        assert_eq!(Ok(Symbol::Blank), "0".parse());
-        assert_eq!(Ok(Symbol::NonBlank), "1".parse());
+        assert_eq!(Ok(Symbol::BLANK), "0".parse());
+        assert_eq!(Ok(Symbol::NON_BLANK), "1".parse());
+        assert_eq!(Ok(Symbol::new(3)), "3".parse());
+        assert_eq!(
+            Err(ParseError::UnknownSymbol("a".to_owned())),
+            "a".parse::<Symbol>()
+        );
     }
 
     #[test]
     fn symbols_all_contain_all_symbols() {
         let actual: Vec<Symbol> = Symbols::all().collect();
 
-        assert_eq!(vec![Symbol::Blank, Symbol::NonBlank], actual);
+        assert_eq!(vec![Symbol::BLANK, Symbol::NON_BLANK], actual);
+    }
+
+    #[test]
+    fn symbols_up_to_contain_the_digits_of_the_alphabet() {
+        let actual: Vec<String> = Symbols::up_to(4).map(|symbol| symbol.to_string()).collect();
+
+        assert_eq!(vec!["0", "1", "2", "3"], actual);
     }
 }
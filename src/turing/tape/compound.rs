@@ -1,10 +1,10 @@
 //! Implementation of a `Tape` using [run-length encoding](https://en.wikipedia.org/wiki/Run-length_encoding) of symbols.
-use super::Tape;
+use super::{Snapshot, Tape};
 use crate::turing::{direction::Direction, symbol::Symbol};
 use std::cmp::Ordering;
 use std::convert::From;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 enum Occurrence {
     Infinite,
     Finite(usize),
@@ -33,6 +33,13 @@ impl Occurrence {
             Occurrence::Finite(n) => *n,
         }
     }
+
+    fn count_or(&self, infinite: usize) -> usize {
+        match self {
+            Occurrence::Infinite => infinite,
+            Occurrence::Finite(n) => *n,
+        }
+    }
 }
 
 impl PartialOrd for Occurrence {
@@ -53,8 +60,13 @@ impl Ord for Occurrence {
 }
 
 /// A `Tape` implementation that use a run-length encoding of symbols
-#[derive(Debug, PartialEq, Eq)]
+///
+/// Tapes are equal when their runs are equal. The position of the tape head is kept for bookkeeping only.
+#[derive(Debug, Eq, Clone)]
 pub struct CompoundTape {
+    head: i128,
+    leftmost: i128,
+    rightmost: i128,
     right: Vec<(Symbol, Occurrence)>,
     left: Vec<(Symbol, Occurrence)>,
 }
@@ -63,16 +75,105 @@ impl CompoundTape {
     /// Create an empty tape
     pub fn empty() -> Self {
         Self {
-            right: vec![(Symbol::Blank, Occurrence::Infinite)],
-            left: vec![(Symbol::Blank, Occurrence::Infinite)],
+            head: 0,
+            leftmost: 0,
+            rightmost: 0,
+            right: vec![(Symbol::BLANK, Occurrence::Infinite)],
+            left: vec![(Symbol::BLANK, Occurrence::Infinite)],
+        }
+    }
+
+    /// Write `symbol` over the run of identical symbols the head is in, moving the head in `direction` until it leaves the run.
+    ///
+    /// At most `limit` cells are rewritten, which matters for the blanks that continue forever. Returns the number of cells that
+    /// were rewritten, i.e. the number of steps a machine that keeps doing the same thing in the run would take.
+    pub fn sweep(&mut self, symbol: Symbol, direction: &Direction, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+        match direction {
+            Direction::Right => {
+                let (s, o) = self.right.pop().unwrap(/* safe because of the sentinel value */);
+                let count = limit.min(o.count_or(limit));
+                take(&mut self.right, (s, o), count);
+                put(&mut self.left, symbol, count);
+                self.head += count as i128;
+                self.rightmost = self.rightmost.max(self.head);
+                count
+            }
+            Direction::Left => {
+                let (s, o) = self.right.pop().unwrap(/* safe because of the sentinel value */);
+                take(&mut self.right, (s, o), 1);
+                let mut count = 1;
+                if let Some((t, p)) = self.left.pop() {
+                    if t == s {
+                        let n = (limit - 1).min(p.count_or(limit));
+                        take(&mut self.left, (t, p), n);
+                        count += n;
+                    } else {
+                        self.left.push((t, p));
+                    }
+                }
+                put(&mut self.right, symbol, count);
+                self.head -= count as i128;
+                self.leftmost = self.leftmost.min(self.head);
+                let (t, p) = self.left.pop().unwrap(/* safe because of the sentinel value */);
+                take(&mut self.left, (t, p), 1);
+                put(&mut self.right, t, 1);
+                count
+            }
         }
     }
 }
 
+/// Put back what remains of a run after `count` of its cells are taken.
+fn take(
+    runs: &mut Vec<(Symbol, Occurrence)>,
+    (symbol, occurrence): (Symbol, Occurrence),
+    count: usize,
+) {
+    match occurrence {
+        Occurrence::Infinite => runs.push((symbol, Occurrence::Infinite)),
+        Occurrence::Finite(n) if n > count => runs.push((symbol, Occurrence::Finite(n - count))),
+        Occurrence::Finite(_) => {}
+    }
+}
+
+/// Put `count` cells with the same symbol on top of runs, merging with the top run when possible.
+fn put(runs: &mut Vec<(Symbol, Occurrence)>, symbol: Symbol, count: usize) {
+    match runs.last_mut() {
+        _ if count == 0 => {}
+        Some((s, Occurrence::Finite(n))) if *s == symbol => *n += count,
+        Some((s, Occurrence::Infinite)) if *s == symbol => {}
+        _ => runs.push((symbol, Occurrence::Finite(count))),
+    }
+}
+
+impl PartialEq for CompoundTape {
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left && self.right == other.right
+    }
+}
+
+fn expand(runs: &[(Symbol, Occurrence)]) -> Vec<Symbol> {
+    runs.iter()
+        .rev()
+        .flat_map(|(symbol, occurrence)| std::iter::repeat_n(*symbol, occurrence.count()))
+        .collect()
+}
+
+impl Default for CompoundTape {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Tape for CompoundTape {
     fn move_to(&mut self, direction: &Direction) {
         match direction {
             Direction::Left => {
+                self.head -= 1;
+                self.leftmost = self.leftmost.min(self.head);
                 let mut p = self.left.pop().unwrap(/* safe because of the sentinel value */);
                 let symbol = p.0;
                 if p.1 > Occurrence::Finite(1) {
@@ -89,6 +190,8 @@ impl Tape for CompoundTape {
                 }
             }
             Direction::Right => {
+                self.head += 1;
+                self.rightmost = self.rightmost.max(self.head);
                 let mut p = self.right.pop().unwrap(/* safe because of the sentinel value */);
                 let symbol = p.0;
                 if p.1 > Occurrence::Finite(1) {
@@ -134,66 +237,99 @@ impl Tape for CompoundTape {
         }
     }
 
-    fn count(&self, target: &Symbol) -> usize {
+    fn count(&self) -> usize {
         self.left
             .iter()
-            .filter(|s| s.0 == *target)
+            .filter(|s| !s.0.is_blank())
             .map(|s| s.1.count())
             .sum::<usize>()
             + self
                 .right
                 .iter()
-                .filter(|s| s.0 == *target)
+                .filter(|s| !s.0.is_blank())
                 .map(|s| s.1.count())
                 .sum::<usize>()
     }
+
+    fn position(&self) -> i128 {
+        self.head
+    }
+
+    fn leftmost(&self) -> i128 {
+        self.leftmost
+    }
+
+    fn rightmost(&self) -> i128 {
+        self.rightmost
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot::new(expand(&self.left), expand(&self.right))
+    }
 }
 
 impl From<(Vec<(Symbol, Occurrence)>, Vec<(Symbol, Occurrence)>)> for CompoundTape {
     fn from(value: (Vec<(Symbol, Occurrence)>, Vec<(Symbol, Occurrence)>)) -> Self {
         let (mut left, mut right) = value;
-        left.push((Symbol::Blank, Occurrence::Infinite));
+        left.push((Symbol::BLANK, Occurrence::Infinite));
         left.reverse();
-        right.push((Symbol::Blank, Occurrence::Infinite));
+        right.push((Symbol::BLANK, Occurrence::Infinite));
         right.reverse();
 
-        Self { left, right }
+        Self {
+            head: 0,
+            leftmost: 0,
+            rightmost: 0,
+            left,
+            right,
+        }
     }
 }
-enum ConvertError {
-    Generic,
-}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::convert::{From, Into};
+    use crate::turing::Footprint;
 
     #[test]
     fn empty_tape_contains_blanks() {
         let tape = CompoundTape::empty();
 
-        assert_eq!(tape.read(), Symbol::Blank);
+        assert_eq!(tape.read(), Symbol::BLANK);
     }
 
     #[test]
     fn tape_can_be_written_to() {
         let mut tape = CompoundTape::empty();
 
-        tape.write(Symbol::NonBlank);
+        tape.write(Symbol::NON_BLANK);
 
-        assert_eq!(tape.read(), Symbol::NonBlank);
+        assert_eq!(tape.read(), Symbol::NON_BLANK);
     }
 
     #[test]
     fn tape_can_count_symbols() {
         let mut tape = CompoundTape::empty();
 
-        tape.write(Symbol::NonBlank);
+        tape.write(Symbol::NON_BLANK);
         tape.move_to(&Direction::Right);
-        tape.write(Symbol::NonBlank);
+        tape.write(Symbol::NON_BLANK);
 
-        assert_eq!(tape.count(&Symbol::NonBlank), 2usize);
+        assert_eq!(tape.count(), 2usize);
+    }
+
+    #[test]
+    fn tape_counts_all_non_blank_symbols() {
+        let mut tape = CompoundTape::empty();
+
+        tape.write(Symbol::new(2));
+        tape.move_to(&Direction::Right);
+        tape.write(Symbol::NON_BLANK);
+        tape.move_to(&Direction::Right);
+        tape.write(Symbol::new(3));
+        tape.write(Symbol::BLANK);
+
+        assert_eq!(tape.count(), 2usize);
     }
 
     #[test]
@@ -201,11 +337,110 @@ mod tests {
         let mut tape = CompoundTape::empty();
 
         for _ in 0..10 {
-            tape.write(Symbol::NonBlank);
+            tape.write(Symbol::NON_BLANK);
+            tape.move_to(&Direction::Right);
+        }
+
+        assert_eq!(tape.count(), 10usize);
+    }
+
+    #[test]
+    fn tape_can_be_snapshot() {
+        let mut tape = CompoundTape::empty();
+
+        tape.write(Symbol::NON_BLANK);
+        tape.move_to(&Direction::Left);
+        tape.move_to(&Direction::Left);
+        tape.write(Symbol::NON_BLANK);
+        tape.move_to(&Direction::Right);
+
+        assert_eq!(tape.position(), -1);
+        assert_eq!(
+            tape.snapshot(),
+            Snapshot::new(
+                vec![Symbol::NON_BLANK],
+                vec![Symbol::BLANK, Symbol::NON_BLANK]
+            )
+        );
+    }
+
+    #[test]
+    fn runs_can_be_swept_in_one_go() {
+        let mut tape = CompoundTape::empty();
+        for _ in 0..10 {
+            tape.write(Symbol::NON_BLANK);
+            tape.move_to(&Direction::Left);
+        }
+        tape.move_to(&Direction::Right);
+
+        assert_eq!(tape.sweep(Symbol::BLANK, &Direction::Right, 100), 10);
+        assert_eq!(tape.position(), 1);
+        assert_eq!(tape.count(), 0);
+
+        tape.move_to(&Direction::Left);
+        assert_eq!(tape.sweep(Symbol::NON_BLANK, &Direction::Left, 5), 5);
+        assert_eq!(tape.position(), -5);
+        assert_eq!(tape.count(), 5);
+    }
+
+    #[test]
+    fn sweeps_agree_with_steps() {
+        let mut swept = CompoundTape::empty();
+        let mut stepped = CompoundTape::empty();
+        for tape in [&mut swept, &mut stepped] {
+            for symbol in [
+                Symbol::NON_BLANK,
+                Symbol::NON_BLANK,
+                Symbol::BLANK,
+                Symbol::NON_BLANK,
+            ] {
+                tape.write(symbol);
+                tape.move_to(&Direction::Left);
+            }
             tape.move_to(&Direction::Right);
         }
 
-        assert_eq!(tape.count(&Symbol::NonBlank), 10usize);
+        let count = swept.sweep(Symbol::BLANK, &Direction::Right, 100);
+        for _ in 0..count {
+            stepped.write(Symbol::BLANK);
+            stepped.move_to(&Direction::Right);
+        }
+        assert_eq!(count, 1);
+        assert_eq!(swept.snapshot(), stepped.snapshot());
+        assert_eq!(swept.position(), stepped.position());
+
+        for _ in 0..2 {
+            swept.move_to(&Direction::Right);
+            stepped.move_to(&Direction::Right);
+        }
+        let count = swept.sweep(Symbol::BLANK, &Direction::Left, 100);
+        for _ in 0..count {
+            stepped.write(Symbol::BLANK);
+            stepped.move_to(&Direction::Left);
+        }
+        assert_eq!(count, 2);
+        assert_eq!(swept.snapshot(), stepped.snapshot());
+        assert_eq!(swept.position(), stepped.position());
+        assert_eq!(swept.footprint(), stepped.footprint());
+    }
+
+    #[test]
+    fn tape_remembers_the_cells_it_visited() {
+        let mut tape = CompoundTape::empty();
+
+        tape.move_to(&Direction::Left);
+        tape.move_to(&Direction::Left);
+        tape.move_to(&Direction::Right);
+        tape.sweep(Symbol::BLANK, &Direction::Right, 4);
+
+        assert_eq!(
+            tape.footprint(),
+            Footprint {
+                cells: 6,
+                leftmost: -2,
+                rightmost: 3
+            }
+        );
     }
 
     #[cfg(test)]
@@ -231,76 +466,76 @@ mod tests {
                 (vec![], vec![]).into(),
             );
             test_sequence(
-                vec![Instruction::Write(Symbol::Blank)],
+                vec![Instruction::Write(Symbol::BLANK)],
                 (vec![], vec![]).into(),
             );
             test_sequence(
-                vec![Instruction::Write(Symbol::NonBlank)],
-                (vec![], vec![(Symbol::NonBlank, Occurrence::Finite(1))]).into(),
+                vec![Instruction::Write(Symbol::NON_BLANK)],
+                (vec![], vec![(Symbol::NON_BLANK, Occurrence::Finite(1))]).into(),
             );
             test_sequence(
                 vec![
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                     Instruction::Move(Direction::Right),
                 ],
-                (vec![(Symbol::NonBlank, Occurrence::Finite(1))], vec![]).into(),
+                (vec![(Symbol::NON_BLANK, Occurrence::Finite(1))], vec![]).into(),
             );
             test_sequence(
                 vec![
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                     Instruction::Move(Direction::Left),
                 ],
                 (
                     vec![],
                     vec![
-                        (Symbol::Blank, Occurrence::Finite(1)),
-                        (Symbol::NonBlank, Occurrence::Finite(1)),
+                        (Symbol::BLANK, Occurrence::Finite(1)),
+                        (Symbol::NON_BLANK, Occurrence::Finite(1)),
                     ],
                 )
                     .into(),
             );
             test_sequence(
                 vec![
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                     Instruction::Move(Direction::Right),
-                    Instruction::Write(Symbol::Blank),
+                    Instruction::Write(Symbol::BLANK),
                 ],
-                (vec![(Symbol::NonBlank, Occurrence::Finite(1))], vec![]).into(),
+                (vec![(Symbol::NON_BLANK, Occurrence::Finite(1))], vec![]).into(),
             );
             test_sequence(
                 vec![
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                     Instruction::Move(Direction::Right),
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                 ],
                 (
-                    vec![(Symbol::NonBlank, Occurrence::Finite(1))],
-                    vec![(Symbol::NonBlank, Occurrence::Finite(1))],
+                    vec![(Symbol::NON_BLANK, Occurrence::Finite(1))],
+                    vec![(Symbol::NON_BLANK, Occurrence::Finite(1))],
                 )
                     .into(),
             );
             test_sequence(
                 vec![
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                     Instruction::Move(Direction::Left),
-                    Instruction::Write(Symbol::Blank),
+                    Instruction::Write(Symbol::BLANK),
                 ],
                 (
                     vec![],
                     vec![
-                        (Symbol::Blank, Occurrence::Finite(1)),
-                        (Symbol::NonBlank, Occurrence::Finite(1)),
+                        (Symbol::BLANK, Occurrence::Finite(1)),
+                        (Symbol::NON_BLANK, Occurrence::Finite(1)),
                     ],
                 )
                     .into(),
             );
             test_sequence(
                 vec![
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                     Instruction::Move(Direction::Left),
-                    Instruction::Write(Symbol::NonBlank),
+                    Instruction::Write(Symbol::NON_BLANK),
                 ],
-                (vec![], vec![(Symbol::NonBlank, Occurrence::Finite(2))]).into(),
+                (vec![], vec![(Symbol::NON_BLANK, Occurrence::Finite(2))]).into(),
             );
         }
 
@@ -320,7 +555,5 @@ mod tests {
                 Instruction::Write(symbol) => tape.write(symbol),
             }
         }
-
-        fn ignore_test_sequence(_: Vec<Instruction>, _: CompoundTape) {}
     }
 }
@@ -16,6 +16,65 @@ pub trait Tape {
     fn read(&self) -> Symbol;
     /// write a symbol to the cell the tape head currently points to.
     fn write(&mut self, symbol: Symbol);
-    /// count the number of occurences of the target symbol on the tape.
-    fn count(&self, target: &Symbol) -> usize;
+    /// count the number of non blank cells on the tape.
+    fn count(&self) -> usize;
+    /// the position of the tape head, relative to the cell it started on.
+    fn position(&self) -> i128;
+    /// the leftmost position of the tape head so far.
+    fn leftmost(&self) -> i128;
+    /// the rightmost position of the tape head so far.
+    fn rightmost(&self) -> i128;
+    /// the number of cells the tape head visited.
+    fn visited(&self) -> usize {
+        (self.rightmost() - self.leftmost() + 1) as usize
+    }
+    /// the part of the tape the tape head visited.
+    fn footprint(&self) -> Footprint {
+        Footprint {
+            cells: self.visited(),
+            leftmost: self.leftmost(),
+            rightmost: self.rightmost(),
+        }
+    }
+    /// the contents of the tape relative to the tape head.
+    fn snapshot(&self) -> Snapshot;
+}
+
+/// The part of a tape that the tape head visited.
+///
+/// The head moves one cell at a time, so it visited every cell between its leftmost and rightmost position.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Footprint {
+    /// The number of cells visited, zero when unknown
+    pub cells: usize,
+    /// The leftmost position of the tape head
+    pub leftmost: i128,
+    /// The rightmost position of the tape head
+    pub rightmost: i128,
+}
+
+/// The contents of a tape relative to the tape head.
+///
+/// Two tapes with the same snapshot only differ by a translation.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Snapshot {
+    /// The symbols to the left of the tape head, nearest first, without trailing blanks.
+    pub left: Vec<Symbol>,
+    /// The symbol under the tape head and the symbols to its right, nearest first, without trailing blanks.
+    pub right: Vec<Symbol>,
+}
+
+impl Snapshot {
+    /// Create a snapshot from the symbols on either side of the tape head, nearest first.
+    pub fn new(mut left: Vec<Symbol>, mut right: Vec<Symbol>) -> Self {
+        trim(&mut left);
+        trim(&mut right);
+        Self { left, right }
+    }
+}
+
+fn trim(symbols: &mut Vec<Symbol>) {
+    while symbols.last() == Some(&Symbol::BLANK) {
+        symbols.pop();
+    }
 }
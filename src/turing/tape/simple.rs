@@ -1,13 +1,15 @@
 /// Naive implementation of a `Tape`.
 ///
 /// Keeps two vectors of symbols, representing all cells to the left and all cells to the right, and an offset into these vectors.
-use super::Tape;
+use super::{Snapshot, Tape};
 use crate::turing::{direction::Direction, symbol::Symbol};
 
 /// A naive implementation of a `Tape`
 #[derive(Debug)]
 pub struct SimpleTape {
     head: Head,
+    leftmost: Head,
+    rightmost: Head,
     right: Vec<Symbol>,
     left: Vec<Symbol>,
 }
@@ -19,6 +21,8 @@ impl SimpleTape {
     pub fn empty() -> Self {
         Self {
             head: 0,
+            leftmost: 0,
+            rightmost: 0,
             right: Vec::new(),
             left: Vec::new(),
         }
@@ -35,11 +39,23 @@ impl SimpleTape {
     }
 }
 
+impl Default for SimpleTape {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Tape for SimpleTape {
     fn move_to(&mut self, direction: &Direction) {
         match direction {
-            Direction::Left => self.head -= 1,
-            Direction::Right => self.head += 1,
+            Direction::Left => {
+                self.head -= 1;
+                self.leftmost = self.leftmost.min(self.head);
+            }
+            Direction::Right => {
+                self.head += 1;
+                self.rightmost = self.rightmost.max(self.head);
+            }
         }
     }
 
@@ -55,21 +71,48 @@ impl Tape for SimpleTape {
         if self.head >= 0i128 {
             let i = self.right_index();
             if i >= self.right.len() {
-                self.right.insert(i, Symbol::Blank)
+                self.right.resize(i + 1, Symbol::BLANK)
             }
             self.right[i] = symbol;
         } else {
             let i = self.left_index();
             if i >= self.left.len() {
-                self.left.insert(i, Symbol::Blank)
+                self.left.resize(i + 1, Symbol::BLANK)
             }
             self.left[i] = symbol
         }
     }
 
-    fn count(&self, target: &Symbol) -> usize {
-        self.left.iter().filter(|s| *s == target).count()
-            + self.right.iter().filter(|s| *s == target).count()
+    fn count(&self) -> usize {
+        self.left.iter().filter(|s| !s.is_blank()).count()
+            + self.right.iter().filter(|s| !s.is_blank()).count()
+    }
+
+    fn position(&self) -> i128 {
+        self.head
+    }
+
+    fn leftmost(&self) -> i128 {
+        self.leftmost
+    }
+
+    fn rightmost(&self) -> i128 {
+        self.rightmost
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let cell = |position: i128| {
+            if position >= 0 {
+                *self.right.get(position as usize).unwrap_or_default()
+            } else {
+                *self.left.get((-position - 1) as usize).unwrap_or_default()
+            }
+        };
+        let leftmost = -(self.left.len() as i128);
+        let rightmost = self.right.len() as i128 - 1;
+        let left = (leftmost..self.head).rev().map(cell).collect();
+        let right = (self.head..=rightmost).map(cell).collect();
+        Snapshot::new(left, right)
     }
 }
 
@@ -79,6 +122,8 @@ impl Clone for SimpleTape {
         let right = self.right.to_vec();
         Self {
             head: self.head,
+            leftmost: self.leftmost,
+            rightmost: self.rightmost,
             left,
             right,
         }
@@ -93,26 +138,75 @@ mod tests {
     fn empty_tape_contains_blanks() {
         let tape = SimpleTape::empty();
 
-        assert_eq!(tape.read(), Symbol::Blank);
+        assert_eq!(tape.read(), Symbol::BLANK);
     }
 
     #[test]
     fn tape_can_be_written_to() {
         let mut tape = SimpleTape::empty();
 
-        tape.write(Symbol::NonBlank);
+        tape.write(Symbol::NON_BLANK);
 
-        assert_eq!(tape.read(), Symbol::NonBlank);
+        assert_eq!(tape.read(), Symbol::NON_BLANK);
     }
 
     #[test]
     fn tape_can_count_symbols() {
         let mut tape = SimpleTape::empty();
 
-        tape.write(Symbol::NonBlank);
+        tape.write(Symbol::NON_BLANK);
+        tape.move_to(&Direction::Right);
+        tape.write(Symbol::NON_BLANK);
+
+        assert_eq!(tape.count(), 2usize);
+    }
+
+    #[test]
+    fn tape_counts_all_non_blank_symbols() {
+        let mut tape = SimpleTape::empty();
+
+        tape.write(Symbol::new(2));
+        tape.move_to(&Direction::Right);
+        tape.write(Symbol::NON_BLANK);
+        tape.move_to(&Direction::Right);
+        tape.write(Symbol::new(3));
+        tape.write(Symbol::BLANK);
+
+        assert_eq!(tape.count(), 2usize);
+    }
+
+    #[test]
+    fn tape_remembers_the_cells_it_visited() {
+        let mut tape = SimpleTape::empty();
+
+        tape.move_to(&Direction::Left);
+        tape.move_to(&Direction::Right);
+        tape.move_to(&Direction::Right);
+        tape.move_to(&Direction::Right);
+        tape.move_to(&Direction::Left);
+
+        assert_eq!(tape.leftmost(), -1);
+        assert_eq!(tape.rightmost(), 2);
+        assert_eq!(tape.visited(), 4);
+    }
+
+    #[test]
+    fn tape_can_be_snapshot() {
+        let mut tape = SimpleTape::empty();
+
+        tape.write(Symbol::NON_BLANK);
+        tape.move_to(&Direction::Left);
+        tape.move_to(&Direction::Left);
+        tape.write(Symbol::NON_BLANK);
         tape.move_to(&Direction::Right);
-        tape.write(Symbol::NonBlank);
 
-        assert_eq!(tape.count(&Symbol::NonBlank), 2usize);
+        assert_eq!(tape.position(), -1);
+        assert_eq!(
+            tape.snapshot(),
+            Snapshot::new(
+                vec![Symbol::NON_BLANK],
+                vec![Symbol::BLANK, Symbol::NON_BLANK]
+            )
+        );
     }
 }
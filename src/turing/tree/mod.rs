@@ -0,0 +1,294 @@
+//! Enumerate programs in [tree normal form](https://bbchallenge.org/method#enumeration).
+//!
+//! Instead of enumerating all complete programs, the tree normal form only determines an action when a Turing machine needs it.
+//! Starting from a program without actions, a machine is run until it reads a key without an action. At that point the program is
+//! extended in all sensible ways, and each extension continues to run from where the machine left of. The leafs of this tree are
+//! the programs whose machine halted or ran for the maximum number of steps.
+//!
+//! Programs that behave the same are only considered once.
+//! * The first action either halts or writes a `1` and moves right. Programs that move left first are mirror images of
+//!   programs that move right first and are accounted for in the multiplicity. Programs that write a `0` first are not
+//!   represented: either they never leave the blank tape, or they behave like a program that starts in an other state.
+//! * States that do not occur in a program are interchangeable, so only the first of them is used as a target. The other
+//!   choices are accounted for in the multiplicity.
+//!
+//! The following code finds the number of steps of the 2-state busy beaver.
+//!
+//! ```
+//! # use busy_beaver::turing::{Assessment, SimpleTape, TreeNormalForm};
+//! let steps = TreeNormalForm::<SimpleTape>::new(2, 100)
+//!     .filter_map(|(_, assessment)| match assessment {
+//!         Assessment::HaltedIn(details) => Some(details.steps),
+//!         _ => None,
+//!     })
+//!     .max();
+//! assert_eq!(Some(6), steps);
+//! ```
+use super::{
+    direction::Direction,
+    machine::{Assessment, Details, Machine, Progress},
+    program::{Action, IncompleteProgram, Key, Program},
+    state::{State, States},
+    symbol::Symbol,
+    tape::Tape,
+};
+use std::cmp::min;
+
+/// Iterator over the programs in tree normal form, together with the assessment of their run.
+pub struct TreeNormalForm<T>
+where
+    T: Tape + Clone + Default,
+{
+    maximum: u128,
+    candidates: Vec<Candidate<T>>,
+}
+
+impl<T> TreeNormalForm<T>
+where
+    T: Tape + Clone + Default,
+{
+    /// Enumerate all programs with `n` states, running each machine for at most `maximum` steps.
+    pub fn new(n: u8, maximum: u128) -> Self {
+        Self::with_symbols(n, 2, maximum)
+    }
+
+    /// Enumerate all programs with `n` states over an alphabet of `symbols` symbols, running each machine for at most
+    /// `maximum` steps.
+    pub fn with_symbols(n: u8, symbols: u8, maximum: u128) -> Self {
+        let mut candidates = roots(n, symbols);
+        candidates.reverse();
+        Self {
+            maximum,
+            candidates,
+        }
+    }
+
+    /// Enumerate all extensions of a program, running each machine for at most `maximum` steps.
+    pub fn extending(program: IncompleteProgram, maximum: u128) -> Self {
+        Self {
+            maximum,
+            candidates: vec![Candidate::new(program)],
+        }
+    }
+}
+
+/// The programs at the root of the tree, i.e. the programs that only determine the first action.
+///
+/// Non blank symbols that do not occur in a program are interchangeable, so the first action only writes a `1`. The other
+/// non blank symbols are accounted for in the multiplicity.
+pub(crate) fn roots<T>(n: u8, symbols: u8) -> Vec<Candidate<T>>
+where
+    T: Tape + Clone + Default,
+{
+    let start: Key = (State::Number(0), Symbol::BLANK).into();
+    let mut halting = IncompleteProgram::with_states_and_symbols(n, symbols);
+    halting.insert(start, Action::Halt);
+    let mut candidates = vec![Candidate::new(halting)];
+    for target in States::non_halted_up_to(min(n, 2)) {
+        let relabelings = match target {
+            State::Number(0) => 1,
+            _ => (n - 1) as usize,
+        };
+        let mut program = IncompleteProgram::with_states_and_symbols(n, symbols)
+            .weighted(2 * relabelings * (symbols - 1) as usize);
+        program.insert(start, (Symbol::NON_BLANK, Direction::Right, target));
+        candidates.push(Candidate::new(program));
+    }
+    candidates
+}
+
+/// A program in the tree, together with the configuration of the machine that runs it.
+pub(crate) struct Candidate<T> {
+    steps: u128,
+    tape: T,
+    state: State,
+    pub(crate) program: IncompleteProgram,
+}
+
+/// What is found when a candidate is explored.
+pub(crate) enum Exploration<T> {
+    /// The candidate is a leaf of the tree
+    Leaf(IncompleteProgram, Box<Assessment>),
+    /// The candidate needs to be extended
+    Branch(Vec<Candidate<T>>),
+}
+
+impl<T> Candidate<T>
+where
+    T: Tape + Clone + Default,
+{
+    fn new(program: IncompleteProgram) -> Self {
+        Self {
+            steps: 0,
+            tape: T::default(),
+            state: State::Number(0),
+            program,
+        }
+    }
+
+    /// Run the machine until it either reaches a leaf or needs its program extended.
+    pub(crate) fn explore(self, maximum: u128) -> Exploration<T> {
+        let program = self.program;
+        let mut steps = self.steps;
+        let mut machine = Machine::with(self.tape, self.state, &program);
+        let assessment = loop {
+            match machine.step() {
+                Progress::Made => {
+                    steps += 1;
+                    if machine.state().halted() {
+                        break Assessment::HaltedIn(Details {
+                            steps,
+                            score: machine.score(),
+                            multiplicity: program.multiplicity(),
+                            footprint: machine.tape().footprint(),
+                        });
+                    }
+                    if steps >= maximum {
+                        break Assessment::NotHalted(Details {
+                            steps,
+                            score: 0,
+                            multiplicity: program.multiplicity(),
+                            footprint: machine.tape().footprint(),
+                        });
+                    }
+                }
+                Progress::Halted => {
+                    break Assessment::HaltedIn(Details {
+                        steps,
+                        score: machine.score(),
+                        multiplicity: program.multiplicity(),
+                        footprint: machine.tape().footprint(),
+                    });
+                }
+                Progress::Limbo => {
+                    let (tape, state, _): (T, State, &dyn Program) = machine.into();
+                    let key: Key = (state, tape.read()).into();
+                    let extensions = program
+                        .extentions(key)
+                        .map(|extension| Candidate {
+                            steps,
+                            tape: tape.clone(),
+                            state,
+                            program: extension,
+                        })
+                        .collect();
+                    return Exploration::Branch(extensions);
+                }
+                Progress::Stuck => {
+                    panic!("Incomplete programs should not get stuck.")
+                }
+            }
+        };
+        Exploration::Leaf(program, Box::new(assessment))
+    }
+}
+
+impl<T> Iterator for TreeNormalForm<T>
+where
+    T: Tape + Clone + Default,
+{
+    type Item = (IncompleteProgram, Assessment);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(candidate) = self.candidates.pop() {
+            match candidate.explore(self.maximum) {
+                Exploration::Leaf(program, assessment) => return Some((program, *assessment)),
+                Exploration::Branch(mut extensions) => {
+                    extensions.reverse();
+                    self.candidates.append(&mut extensions);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing::{CompleteProgram, Programs, SimpleTape};
+
+    #[test]
+    fn multiplicities_add_up_to_the_represented_programs() {
+        let total: usize = TreeNormalForm::<SimpleTape>::new(2, 100)
+            .map(|(_, assessment)| assessment.details().multiplicity)
+            .sum();
+
+        // The first action halts or writes a 1 and moves in either direction to either state.
+        assert_eq!(5 * 9usize.pow(3), total);
+    }
+
+    #[test]
+    fn tree_normal_form_agrees_with_all_programs() {
+        let maximum = 50;
+        let halted_in_tree: usize = TreeNormalForm::<SimpleTape>::new(2, maximum)
+            .filter(|(_, assessment)| matches!(assessment, Assessment::HaltedIn(_)))
+            .map(|(_, assessment)| assessment.details().multiplicity)
+            .sum();
+
+        let halted_in_all = Programs::all(2)
+            .filter(writes_first_or_halts)
+            .filter(|program| {
+                let mut machine = Machine::new(SimpleTape::empty(), program);
+                matches!(machine.run(maximum), Assessment::HaltedIn(_))
+            })
+            .count();
+
+        assert_eq!(halted_in_all, halted_in_tree);
+    }
+
+    #[test]
+    fn tree_normal_form_agrees_with_all_programs_over_larger_alphabets() {
+        let maximum = 50;
+        for (n, symbols) in [(1, 3), (1, 4)] {
+            let halted_in_tree: usize =
+                TreeNormalForm::<SimpleTape>::with_symbols(n, symbols, maximum)
+                    .filter(|(_, assessment)| matches!(assessment, Assessment::HaltedIn(_)))
+                    .map(|(_, assessment)| assessment.details().multiplicity)
+                    .sum();
+
+            let halted_in_all = Programs::all_with_symbols(n, symbols)
+                .filter(|program| {
+                    let start: Key = (State::Number(0), Symbol::BLANK).into();
+                    let action: Option<Action> = program.lookup(&start).into();
+                    !matches!(
+                        action,
+                        Some(Action::Do {
+                            symbol: Symbol::BLANK,
+                            ..
+                        })
+                    )
+                })
+                .filter(|program| {
+                    let mut machine = Machine::new(SimpleTape::empty(), program);
+                    matches!(machine.run(maximum), Assessment::HaltedIn(_))
+                })
+                .count();
+
+            assert_eq!(halted_in_all, halted_in_tree);
+        }
+    }
+
+    fn writes_first_or_halts(program: &CompleteProgram) -> bool {
+        let start: Key = (State::Number(0), Symbol::BLANK).into();
+        let action: Option<Action> = program.lookup(&start).into();
+        matches!(
+            action,
+            Some(Action::Halt)
+                | Some(Action::Do {
+                    symbol: Symbol::NON_BLANK,
+                    ..
+                })
+        )
+    }
+
+    #[test]
+    fn programs_are_extended_only_when_needed() {
+        let leafs: Vec<String> = TreeNormalForm::<SimpleTape>::new(2, 100)
+            .map(|(program, _)| program.to_string())
+            .take(2)
+            .collect();
+
+        assert_eq!(vec!["  H ??? ??? ???", "1R0 ??? ??? ???"], leafs);
+    }
+}
@@ -0,0 +1,322 @@
+//! The text format of certificates.
+use super::Certificate;
+use crate::decider::{Language, Local, Reduction};
+use crate::turing::Symbol;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Problems with reading a certificate.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The certificate is empty
+    NoInput,
+    /// The certificate starts with an unknown kind of proof
+    UnknownKind(String),
+    /// A line of the certificate, counting from zero, can not be read
+    MalformedLine(usize),
+}
+
+impl Display for Certificate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Certificate::Cycle { preperiod, period } => {
+                writeln!(f, "cycle {} {}", preperiod, period)
+            }
+            Certificate::TranslatedCycle {
+                preperiod,
+                period,
+                distance,
+            } => writeln!(f, "translated {} {} {}", preperiod, period, distance),
+            Certificate::Language(language) => {
+                writeln!(f, "language {}", language.k)?;
+                writeln!(f, "left {}", windows(&language.left))?;
+                writeln!(f, "right {}", windows(&language.right))?;
+                let mut locals: Vec<String> = language
+                    .local
+                    .iter()
+                    .map(|local| {
+                        format!(
+                            "local {} {} {} {}",
+                            local.state,
+                            word(&local.left),
+                            local.head,
+                            word(&local.right)
+                        )
+                    })
+                    .collect();
+                locals.sort();
+                for local in locals {
+                    writeln!(f, "{}", local)?;
+                }
+                Ok(())
+            }
+            Certificate::Reduction(reduction) => {
+                writeln!(f, "reduction")?;
+                let dfa: Vec<String> = reduction
+                    .dfa
+                    .iter()
+                    .map(|transitions| format!("{},{}", transitions[0], transitions[1]))
+                    .collect();
+                writeln!(f, "dfa {}", dfa.join(" "))?;
+                for transitions in &reduction.nfa {
+                    writeln!(
+                        f,
+                        "nfa {} {}",
+                        states(&transitions[0]),
+                        states(&transitions[1])
+                    )?;
+                }
+                for entry in &reduction.entries {
+                    writeln!(f, "entry {}", states(entry))?;
+                }
+                let accepting: String = reduction
+                    .accepting
+                    .iter()
+                    .map(|accepting| if *accepting { '1' } else { '0' })
+                    .collect();
+                writeln!(f, "accepting {}", accepting)
+            }
+        }
+    }
+}
+
+fn word(symbols: &[Symbol]) -> String {
+    symbols.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+fn windows(windows: &HashSet<Vec<Symbol>>) -> String {
+    let mut words: Vec<String> = windows.iter().map(|window| word(window)).collect();
+    words.sort();
+    words.join(" ")
+}
+
+fn states(states: &[usize]) -> String {
+    if states.is_empty() {
+        "-".to_owned()
+    } else {
+        let states: Vec<String> = states.iter().map(|state| state.to_string()).collect();
+        states.join(",")
+    }
+}
+
+impl FromStr for Certificate {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| (index, line.split_whitespace().collect::<Vec<&str>>()));
+        let (index, first) = lines.next().ok_or(ParseError::NoInput)?;
+        match first.as_slice() {
+            ["cycle", preperiod, period] => {
+                let certificate = Certificate::Cycle {
+                    preperiod: parse(preperiod, index)?,
+                    period: parse(period, index)?,
+                };
+                match lines.next() {
+                    Some((index, _)) => Err(ParseError::MalformedLine(index)),
+                    None => Ok(certificate),
+                }
+            }
+            ["translated", preperiod, period, distance] => {
+                let certificate = Certificate::TranslatedCycle {
+                    preperiod: parse(preperiod, index)?,
+                    period: parse(period, index)?,
+                    distance: parse(distance, index)?,
+                };
+                match lines.next() {
+                    Some((index, _)) => Err(ParseError::MalformedLine(index)),
+                    None => Ok(certificate),
+                }
+            }
+            ["language", k] => {
+                let mut language = Language {
+                    k: parse(k, index)?,
+                    left: HashSet::new(),
+                    right: HashSet::new(),
+                    local: HashSet::new(),
+                };
+                for (index, line) in lines {
+                    match line.as_slice() {
+                        ["left", windows @ ..] => {
+                            for window in windows {
+                                language.left.insert(
+                                    symbols(window).ok_or(ParseError::MalformedLine(index))?,
+                                );
+                            }
+                        }
+                        ["right", windows @ ..] => {
+                            for window in windows {
+                                language.right.insert(
+                                    symbols(window).ok_or(ParseError::MalformedLine(index))?,
+                                );
+                            }
+                        }
+                        ["local", state, left, head, right] => {
+                            let head = match symbols(head).as_deref() {
+                                Some([head]) => *head,
+                                _ => return Err(ParseError::MalformedLine(index)),
+                            };
+                            language.local.insert(Local {
+                                state: parse(state, index)?,
+                                left: symbols(left).ok_or(ParseError::MalformedLine(index))?,
+                                head,
+                                right: symbols(right).ok_or(ParseError::MalformedLine(index))?,
+                            });
+                        }
+                        _ => return Err(ParseError::MalformedLine(index)),
+                    }
+                }
+                Ok(Certificate::Language(language))
+            }
+            ["reduction"] => {
+                let mut reduction = Reduction {
+                    dfa: Vec::new(),
+                    nfa: Vec::new(),
+                    entries: Vec::new(),
+                    accepting: Vec::new(),
+                };
+                for (index, line) in lines {
+                    match line.as_slice() {
+                        ["dfa", transitions @ ..] => {
+                            for transition in transitions {
+                                let targets =
+                                    targets(transition).ok_or(ParseError::MalformedLine(index))?;
+                                match targets.as_slice() {
+                                    [blank, non_blank] => reduction.dfa.push([*blank, *non_blank]),
+                                    _ => return Err(ParseError::MalformedLine(index)),
+                                }
+                            }
+                        }
+                        ["nfa", blank, non_blank] => reduction.nfa.push([
+                            targets(blank).ok_or(ParseError::MalformedLine(index))?,
+                            targets(non_blank).ok_or(ParseError::MalformedLine(index))?,
+                        ]),
+                        ["entry", entry] => reduction
+                            .entries
+                            .push(targets(entry).ok_or(ParseError::MalformedLine(index))?),
+                        ["accepting", accepting] => {
+                            for accepting in accepting.chars() {
+                                match accepting {
+                                    '0' => reduction.accepting.push(false),
+                                    '1' => reduction.accepting.push(true),
+                                    _ => return Err(ParseError::MalformedLine(index)),
+                                }
+                            }
+                        }
+                        _ => return Err(ParseError::MalformedLine(index)),
+                    }
+                }
+                Ok(Certificate::Reduction(reduction))
+            }
+            [kind, ..] => Err(ParseError::UnknownKind((*kind).to_owned())),
+            [] => Err(ParseError::NoInput),
+        }
+    }
+}
+
+fn parse<T: FromStr>(input: &str, index: usize) -> Result<T, ParseError> {
+    input.parse().map_err(|_| ParseError::MalformedLine(index))
+}
+
+/// The symbols of a word over an alphabet of two symbols.
+fn symbols(word: &str) -> Option<Vec<Symbol>> {
+    word.chars()
+        .map(|symbol| {
+            symbol
+                .to_string()
+                .parse::<Symbol>()
+                .ok()
+                .filter(|symbol| symbol.digit() < 2)
+        })
+        .collect()
+}
+
+fn targets(targets: &str) -> Option<Vec<usize>> {
+    if targets == "-" {
+        Some(Vec::new())
+    } else {
+        targets
+            .split(',')
+            .map(|target| target.parse().ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decider::{ClosedTapeLanguage, FiniteAutomataReduction};
+    use crate::turing::CompleteProgram;
+
+    fn round_trip(certificate: Certificate) {
+        let text = certificate.to_string();
+
+        assert_eq!(Ok(certificate), text.parse());
+    }
+
+    #[test]
+    fn cycles_can_be_written_and_read() {
+        round_trip(Certificate::Cycle {
+            preperiod: 3,
+            period: 4,
+        });
+        round_trip(Certificate::TranslatedCycle {
+            preperiod: 3,
+            period: 4,
+            distance: -2,
+        });
+    }
+
+    #[test]
+    fn automata_can_be_written_and_read() {
+        let program: CompleteProgram = "1L1   H 0R2 0L1 1L1 1R2".parse().unwrap();
+
+        let (_, language) = ClosedTapeLanguage::new(3).prove(&program).unwrap();
+        round_trip(Certificate::Language(language));
+        let (_, reduction) = FiniteAutomataReduction::new(3).prove(&program).unwrap();
+        round_trip(Certificate::Reduction(reduction));
+    }
+
+    #[test]
+    fn comments_and_empty_lines_are_ignored() {
+        let certificate: Certificate = "# a cycle\n\ncycle 0 4\n".parse().unwrap();
+
+        assert_eq!(
+            Certificate::Cycle {
+                preperiod: 0,
+                period: 4
+            },
+            certificate
+        );
+    }
+
+    #[test]
+    fn unknown_kinds_are_rejected() {
+        assert_eq!(
+            Err(ParseError::UnknownKind("bouncer".to_owned())),
+            "bouncer 1".parse::<Certificate>()
+        );
+    }
+
+    #[test]
+    fn malformed_lines_are_reported() {
+        assert_eq!(
+            Err(ParseError::MalformedLine(0)),
+            "cycle zero 4".parse::<Certificate>()
+        );
+        assert_eq!(
+            Err(ParseError::MalformedLine(1)),
+            "cycle 0 4\nleft 0".parse::<Certificate>()
+        );
+        assert_eq!(
+            Err(ParseError::MalformedLine(2)),
+            "language 1\nleft 0\nright 2".parse::<Certificate>()
+        );
+    }
+}
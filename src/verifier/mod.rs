@@ -0,0 +1,495 @@
+//! Check certificates that Turing machines never halt, without trusting the code that found them.
+//!
+//! A certificate is checked against a program with nothing but lookups of the actions for keys. None of the deciders is run and
+//! nothing is searched for: a certificate either shows what it claims, or it is rejected.
+//!
+//! Certificates are written in a line based text format. Empty lines and lines starting with `#` are ignored. The first line
+//! tells the kind of proof.
+//!
+//! * `cycle <preperiod> <period>`: after `preperiod` steps the configuration repeats every `period` steps.
+//! * `translated <preperiod> <period> <distance>`: after `preperiod` steps the configuration repeats every `period` steps,
+//!   shifted `distance` cells along the tape.
+//! * `language <k>`: a closed tape language with windows of `k` symbols, followed by a `left` and a `right` line with the
+//!   windows, and a `local <state> <left> <head> <right>` line for each configuration near the head.
+//! * `reduction`: a finite automata reduction, followed by a `dfa` line with the transitions `<blank>,<non-blank>` of each DFA
+//!   state, a `nfa <blank> <non-blank>` line for each NFA state with comma separated targets or `-` when there are none, an
+//!   `entry` line for each combination of DFA state and key with the NFA states or `-`, and an `accepting` line of `0`s and
+//!   `1`s.
+//!
+//! The following code checks a certificate
+//!
+//! ```
+//! # use busy_beaver::turing::CompleteProgram;
+//! # use busy_beaver::verifier::{verify, Certificate};
+//! let program: CompleteProgram = "1R1 0R1 0L0 1L0".parse().unwrap();
+//! let certificate: Certificate = "cycle 0 4".parse().unwrap();
+//!
+//! assert!(verify(&program, &certificate));
+//! ```
+mod format;
+
+use crate::decider::{self, Language, Reduction, Verdict};
+use crate::turing::{Action, Assessment, Direction, Key, Lookup, Program, State, Symbol};
+pub use format::ParseError;
+use std::collections::{HashMap, HashSet};
+
+/// A proof that a Turing machine never halts.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Certificate {
+    /// The configuration repeats
+    Cycle {
+        /// The number of steps before the configuration repeats
+        preperiod: u128,
+        /// The number of steps after which the configuration repeats
+        period: u128,
+    },
+    /// The configuration repeats, shifted along the tape
+    TranslatedCycle {
+        /// The number of steps before the configuration repeats
+        preperiod: u128,
+        /// The number of steps after which the configuration repeats
+        period: u128,
+        /// How far the configuration shifted in a period, negative to the left
+        distance: i128,
+    },
+    /// The configurations stay within a closed tape language
+    Language(Language),
+    /// The halting configurations form a regular language that does not contain the start configuration
+    Reduction(Reduction),
+}
+
+impl Certificate {
+    /// The certificate contained in a verdict, if any.
+    ///
+    /// Cycles are read from the assessment. Closed tape languages and finite automata reductions come from the certificate of the
+    /// decider that settled the program.
+    pub fn of(verdict: &Verdict) -> Option<Self> {
+        match &verdict.certificate {
+            Some(decider::Certificate::Language(language)) => {
+                return Some(Certificate::Language(*language.clone()))
+            }
+            Some(decider::Certificate::Reduction(reduction)) => {
+                return Some(Certificate::Reduction(*reduction.clone()))
+            }
+            _ => {}
+        }
+        match verdict.assessment {
+            Assessment::Cycles {
+                preperiod, period, ..
+            } => Some(Certificate::Cycle { preperiod, period }),
+            Assessment::TranslatedCycles {
+                preperiod,
+                period,
+                distance,
+                ..
+            } => Some(Certificate::TranslatedCycle {
+                preperiod,
+                period,
+                distance,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Check that a certificate proves that a program never halts.
+///
+/// Certificates are only accepted for programs over an alphabet of two symbols.
+pub fn verify(program: &dyn Program, certificate: &Certificate) -> bool {
+    if program.symbols() != 2 {
+        return false;
+    }
+    match certificate {
+        Certificate::Cycle { preperiod, period } => cycle(program, *preperiod, *period),
+        Certificate::TranslatedCycle {
+            preperiod,
+            period,
+            distance,
+        } => translated_cycle(program, *preperiod, *period, *distance),
+        Certificate::Language(language) => closed_language(program, language),
+        Certificate::Reduction(reduction) => reduction_rejects_start(program, reduction),
+    }
+}
+
+/// The action for a key, when it moves the head without halting. An action that enters the halted state halts.
+fn action(program: &dyn Program, key: Key) -> Option<(Symbol, Direction, State)> {
+    match program.lookup(&key) {
+        Lookup::Determined(Action::Do {
+            symbol,
+            direction,
+            state: state @ State::Number(_),
+        }) => Some((symbol, direction, state)),
+        _ => None,
+    }
+}
+
+/// The number of keys a program has an action for, i.e. twice its number of states.
+fn keys(program: &dyn Program) -> usize {
+    (0..2 * u8::MAX as usize)
+        .take_while(|index| !matches!(program.lookup(&Key::from(*index)), Lookup::Unknown))
+        .count()
+}
+
+fn symbol_index(symbol: Symbol) -> usize {
+    symbol.digit() as usize
+}
+
+const SYMBOLS: [Symbol; 2] = [Symbol::BLANK, Symbol::NON_BLANK];
+
+/// A configuration of a Turing machine that only remembers the non-blank cells.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Run {
+    state: State,
+    head: i128,
+    tape: HashMap<i128, Symbol>,
+}
+
+impl Run {
+    fn start() -> Self {
+        Self {
+            state: State::Number(0),
+            head: 0,
+            tape: HashMap::new(),
+        }
+    }
+
+    fn read(&self, position: i128) -> Symbol {
+        self.tape.get(&position).copied().unwrap_or(Symbol::BLANK)
+    }
+
+    /// Take a step. Returns `false` when the machine halts instead.
+    fn step(&mut self, program: &dyn Program) -> bool {
+        match action(program, (self.state, self.read(self.head)).into()) {
+            Some((symbol, direction, state)) => {
+                if symbol.is_blank() {
+                    self.tape.remove(&self.head);
+                } else {
+                    self.tape.insert(self.head, symbol);
+                }
+                self.head += match direction {
+                    Direction::Left => -1,
+                    Direction::Right => 1,
+                };
+                self.state = state;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn steps(&mut self, program: &dyn Program, steps: u128) -> bool {
+        (0..steps).all(|_| self.step(program))
+    }
+}
+
+fn cycle(program: &dyn Program, preperiod: u128, period: u128) -> bool {
+    let mut run = Run::start();
+    if period == 0 || !run.steps(program, preperiod) {
+        return false;
+    }
+    let start = run.clone();
+    run.steps(program, period) && run == start
+}
+
+/// The run after the preperiod only reads cells from `visited` onward in the direction of `distance`. When those cells reappear
+/// shifted by `distance` after the period, the run repeats itself shifted forever.
+fn translated_cycle(program: &dyn Program, preperiod: u128, period: u128, distance: i128) -> bool {
+    let mut run = Run::start();
+    if period == 0 || distance == 0 || !run.steps(program, preperiod) {
+        return false;
+    }
+    let start = run.clone();
+    let mut visited = run.head;
+    for _ in 0..period {
+        if !run.step(program) {
+            return false;
+        }
+        visited = if distance > 0 {
+            visited.min(run.head)
+        } else {
+            visited.max(run.head)
+        };
+    }
+    let ahead = |position: i128| {
+        if distance > 0 {
+            position >= visited
+        } else {
+            position <= visited
+        }
+    };
+    run.state == start.state
+        && run.head == start.head + distance
+        && start
+            .tape
+            .keys()
+            .copied()
+            .chain(run.tape.keys().map(|position| position - distance))
+            .filter(|position| ahead(*position))
+            .all(|position| start.read(position) == run.read(position + distance))
+}
+
+/// A closed tape language contains the start configuration, never halts and every step stays within the language.
+fn closed_language(program: &dyn Program, language: &Language) -> bool {
+    let k = language.k;
+    let blank = vec![Symbol::BLANK; k];
+    let sized = |window: &Vec<Symbol>| window.len() == k;
+    if k == 0
+        || !language.left.iter().all(sized)
+        || !language.right.iter().all(sized)
+        || !language.left.contains(&blank)
+        || !language.right.contains(&blank)
+        || !language
+            .local
+            .iter()
+            .all(|local| sized(&local.left) && sized(&local.right))
+    {
+        return false;
+    }
+    let contains = |state: State, left: &[Symbol], head: Symbol, right: &[Symbol]| {
+        language.local.iter().any(|local| {
+            local.state == state && local.left == left && local.head == head && local.right == right
+        })
+    };
+    contains(State::Number(0), &blank, Symbol::BLANK, &blank)
+        && language.local.iter().all(|local| {
+            match action(program, (local.state, local.head).into()) {
+                Some((symbol, Direction::Right, state)) => {
+                    let mut left = vec![symbol];
+                    left.extend_from_slice(&local.left[..k - 1]);
+                    language.left.contains(&left)
+                        && SYMBOLS.iter().all(|next| {
+                            let mut right = local.right[1..].to_vec();
+                            right.push(*next);
+                            !language.right.contains(&right)
+                                || contains(state, &left, local.right[0], &right)
+                        })
+                }
+                Some((symbol, Direction::Left, state)) => {
+                    let mut right = vec![symbol];
+                    right.extend_from_slice(&local.right[..k - 1]);
+                    language.right.contains(&right)
+                        && SYMBOLS.iter().all(|next| {
+                            let mut left = local.left[1..].to_vec();
+                            left.push(*next);
+                            !language.left.contains(&left)
+                                || contains(state, &left, local.left[0], &right)
+                        })
+                }
+                None => false,
+            }
+        })
+}
+
+/// A finite automata reduction accepts every halting configuration, accepts a configuration whenever it accepts its successor
+/// and rejects the start configuration.
+fn reduction_rejects_start(program: &dyn Program, reduction: &Reduction) -> bool {
+    let dfa = &reduction.dfa;
+    let nfa = &reduction.nfa;
+    let states = reduction.accepting.len();
+    let keys = keys(program);
+    if dfa.is_empty()
+        || dfa[0][0] != 0
+        || dfa.iter().flatten().any(|target| *target >= dfa.len())
+        || nfa.len() != states
+        || keys == 0
+        || reduction.entries.len() != dfa.len() * keys
+        || nfa
+            .iter()
+            .flatten()
+            .chain(reduction.entries.iter())
+            .flatten()
+            .any(|state| *state >= states)
+    {
+        return false;
+    }
+    let entry = |left: usize, key: Key| -> HashSet<usize> {
+        reduction.entries[left * keys + key.idx()]
+            .iter()
+            .copied()
+            .collect()
+    };
+    let read = |from: &HashSet<usize>, symbol: Symbol| -> HashSet<usize> {
+        from.iter()
+            .flat_map(|state| nfa[*state][symbol_index(symbol)].iter().copied())
+            .collect()
+    };
+    let accepts_everything = |state: usize| {
+        reduction.accepting[state]
+            && nfa[state][0].contains(&state)
+            && nfa[state][1].contains(&state)
+    };
+    let closed = (0..dfa.len()).all(|left| {
+        (0..keys).map(Key::from).all(|key| {
+            let current = entry(left, key);
+            match action(program, key) {
+                Some((symbol, Direction::Right, state)) => SYMBOLS.iter().all(|next| {
+                    let successor = entry(dfa[left][symbol_index(symbol)], (state, *next).into());
+                    successor.is_subset(&read(&current, *next))
+                }),
+                Some((symbol, Direction::Left, state)) => (0..dfa.len()).all(|previous| {
+                    SYMBOLS
+                        .iter()
+                        .filter(|next| dfa[previous][symbol_index(**next)] == left)
+                        .all(|next| {
+                            let successor = entry(previous, (state, *next).into());
+                            read(&successor, symbol).is_subset(&current)
+                        })
+                }),
+                None => current.iter().any(|state| accepts_everything(*state)),
+            }
+        })
+    });
+    let mut reached = entry(0, (State::Number(0), Symbol::BLANK).into());
+    loop {
+        let next: HashSet<usize> = reached
+            .union(&read(&reached, Symbol::BLANK))
+            .copied()
+            .collect();
+        if next.len() == reached.len() {
+            break;
+        }
+        reached = next;
+    }
+    closed && reached.iter().all(|state| !reduction.accepting[*state])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decider::{
+        ClosedTapeLanguage, CycleDetector, FiniteAutomataReduction, Pipeline, TranslatedCycler,
+    };
+    use crate::turing::CompleteProgram;
+
+    #[test]
+    fn cycles_are_verified() {
+        let program: CompleteProgram = "1R1 0R1 0L0 1L0".parse().unwrap();
+        let verdict = Pipeline::new().with(CycleDetector::new(100)).run(&program);
+        let certificate = Certificate::of(&verdict).unwrap();
+
+        assert!(verify(&program, &certificate));
+        assert!(!verify(
+            &program,
+            &Certificate::Cycle {
+                preperiod: 0,
+                period: 3
+            }
+        ));
+    }
+
+    #[test]
+    fn translated_cycles_are_verified() {
+        for program in ["1R0 1R0 1R0 1R0", "1L1 0L0 1R0 0L0", "1L1 1R0 1R0 0R1"] {
+            let program: CompleteProgram = program.parse().unwrap();
+            let verdict = Pipeline::new()
+                .with(TranslatedCycler::new(100))
+                .run(&program);
+            let certificate = Certificate::of(&verdict).unwrap();
+
+            assert!(verify(&program, &certificate));
+        }
+    }
+
+    #[test]
+    fn translated_cycles_must_shift_what_is_read() {
+        let program: CompleteProgram = "1L1 1R0 1R0 0R1".parse().unwrap();
+
+        assert!(!verify(
+            &program,
+            &Certificate::TranslatedCycle {
+                preperiod: 0,
+                period: 4,
+                distance: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn closed_languages_are_verified() {
+        let program: CompleteProgram = "1L1   H 0R2 0L1 1L1 1R2".parse().unwrap();
+        let verdict = Pipeline::new()
+            .with(ClosedTapeLanguage::new(3))
+            .run(&program);
+        let certificate = Certificate::of(&verdict).unwrap();
+
+        assert!(verify(&program, &certificate));
+
+        if let Certificate::Language(mut language) = certificate {
+            language
+                .local
+                .retain(|local| local.state != State::Number(0));
+            assert!(!verify(&program, &Certificate::Language(language)));
+        }
+    }
+
+    #[test]
+    fn reductions_are_verified() {
+        let program: CompleteProgram = "1L1   H 0R2 0L1 1L1 1R2".parse().unwrap();
+        let verdict = Pipeline::new()
+            .with(FiniteAutomataReduction::new(3))
+            .run(&program);
+        let certificate = Certificate::of(&verdict).unwrap();
+
+        assert!(verify(&program, &certificate));
+
+        if let Certificate::Reduction(mut reduction) = certificate {
+            reduction.accepting = vec![true; reduction.accepting.len()];
+            assert!(!verify(&program, &Certificate::Reduction(reduction)));
+        }
+    }
+
+    #[test]
+    fn reductions_must_have_an_entry_for_every_key() {
+        let program: CompleteProgram = "1L1   H 0R2 0L1 1L1 1R2".parse().unwrap();
+        let verdict = Pipeline::new()
+            .with(FiniteAutomataReduction::new(3))
+            .run(&program);
+
+        if let Some(Certificate::Reduction(reduction)) = Certificate::of(&verdict) {
+            let mut truncated = reduction.clone();
+            truncated.entries.pop();
+            assert!(!verify(&program, &Certificate::Reduction(truncated)));
+
+            let mut padded = reduction.clone();
+            padded
+                .entries
+                .extend(reduction.entries[..reduction.dfa.len()].iter().cloned());
+            assert!(!verify(&program, &Certificate::Reduction(padded)));
+        } else {
+            panic!("expected a reduction");
+        }
+
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+        let certificate: Certificate = "reduction\ndfa 0,0\nnfa - -\nentry -\naccepting 0"
+            .parse()
+            .unwrap();
+        assert!(!verify(&program, &certificate));
+    }
+
+    #[test]
+    fn programs_that_write_before_halting_are_never_verified() {
+        let program: CompleteProgram = "1RH 1RH 1RH 1RH".parse().unwrap();
+        let certificate: Certificate = "translated 1 1 1".parse().unwrap();
+
+        assert!(!verify(&program, &certificate));
+    }
+
+    #[test]
+    fn programs_with_more_than_two_symbols_are_never_verified() {
+        let program = CompleteProgram::parse_with_symbols("1R0 1R0 1R0", 3).unwrap();
+        let certificate: Certificate = "translated 0 1 1".parse().unwrap();
+
+        assert!(!verify(&program, &certificate));
+    }
+
+    #[test]
+    fn halting_programs_are_never_verified() {
+        let program: CompleteProgram = "1R1 1L1 1L0   H".parse().unwrap();
+
+        for preperiod in 0..10 {
+            for period in 1..10 {
+                assert!(!verify(&program, &Certificate::Cycle { preperiod, period }));
+            }
+        }
+    }
+}
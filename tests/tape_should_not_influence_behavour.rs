@@ -1,4 +1,4 @@
-use busy_beaver::turing::{CompleteProgram, CompoundTape, Machine, Programs, SimpleTape};
+use busy_beaver::turing::{CompleteProgram, CompoundTape, Machine, Programs, SimpleTape, Tape};
 
 #[test]
 fn compound_tape_results_in_same_behavour_as_simple_tape_on_various_programs() {
@@ -32,3 +32,21 @@ fn compound_tape_results_in_same_behavour_as_simple_tape_for_all_progams_with_tw
         assert_eq!(assessment_of_simple_tape, assessment_of_compound_tape);
     }
 }
+
+#[test]
+fn skipping_runs_results_in_same_behavour_as_stepping_for_all_progams_with_two_states() {
+    for program in Programs::all(2) {
+        println!("{}", program);
+        let mut stepping_machine = Machine::new(CompoundTape::empty(), &program);
+        let mut skipping_machine = Machine::new(CompoundTape::empty(), &program);
+
+        let assessment_of_stepping = stepping_machine.run(100);
+        let assessment_of_skipping = skipping_machine.run_skipping(100);
+
+        assert_eq!(assessment_of_stepping, assessment_of_skipping);
+        assert_eq!(
+            stepping_machine.tape().snapshot(),
+            skipping_machine.tape().snapshot()
+        );
+    }
+}